@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.22;
+
+import { OFTMsgCodec } from "@layerzerolabs/oft-evm/contracts/libs/OFTMsgCodec.sol";
+
+/**
+ * @title OFTMsgCodecHarness
+ * @notice Exposes OFTMsgCodec.encode, which produces the message vectors
+ *         checked by the Solana OFT's msg_codec tests
+ * @dev composeFrom is msg.sender, so call from the address the vectors use
+ *      (0x70997970C51812dc3A010C7d01b50e0d17dc79C8, the second Hardhat account)
+ */
+contract OFTMsgCodecHarness {
+    function encode(
+        bytes32 _sendTo,
+        uint64 _amountShared,
+        bytes calldata _composeMsg
+    ) external view returns (bytes memory message, bool hasCompose) {
+        return OFTMsgCodec.encode(_sendTo, _amountShared, _composeMsg);
+    }
+}
@@ -17,6 +17,7 @@ pub mod bonk_oft_wrapper {
         let config = &mut ctx.accounts.config;
 
         config.admin = ctx.accounts.admin.key();
+        config.pending_admin = None;
         config.bonk_mint = BONK_MINT_PUBKEY;
         config.wrapped_mint = ctx.accounts.wrapped_mint.key();
         config.total_wrapped = 0;
@@ -146,6 +147,99 @@ pub mod bonk_oft_wrapper {
         config.paused = paused;
         Ok(())
     }
+
+    /// Propose a new admin
+    /// The admin only changes once the proposed key accepts
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_admin = Some(new_admin);
+
+        emit!(AdminTransferProposed {
+            admin: config.admin,
+            pending_admin: new_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Proposed admin transfer to {}", new_admin);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin transfer, signed by the proposed admin
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_admin = config.admin;
+        config.admin = ctx.accounts.pending_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminTransferred {
+            old_admin,
+            new_admin: config.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Admin transferred from {} to {}", old_admin, config.admin);
+
+        Ok(())
+    }
+
+    /// Cancel a pending admin transfer
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let pending_admin = config.pending_admin.take().ok_or(WrapperError::NoPendingAdmin)?;
+
+        emit!(AdminTransferCancelled {
+            admin: config.admin,
+            pending_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Cancelled admin transfer to {}", pending_admin);
+
+        Ok(())
+    }
+
+    /// Grow a config created before `pending_admin` to the current layout
+    /// The new field reads as `None` from the zeroed tail
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let config = ctx.accounts.config.to_account_info();
+        let space = 8 + WrapperConfig::INIT_SPACE;
+        require!(config.data_len() < space, WrapperError::AlreadyMigrated);
+
+        {
+            let data = config.try_borrow_data()?;
+            require!(
+                data.len() >= 40 && data[..8] == *WrapperConfig::DISCRIMINATOR,
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            require_keys_eq!(
+                Pubkey::new_from_array(data[8..40].try_into().unwrap()),
+                ctx.accounts.admin.key(),
+                WrapperError::Unauthorized
+            );
+        }
+
+        let top_up = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(config.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: config.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        config.resize(space)?;
+
+        msg!("Config migrated");
+
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -315,6 +409,62 @@ pub struct SetPaused<'info> {
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, WrapperConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(pending_admin.key()) @ WrapperError::NotPendingAdmin
+    )]
+    pub config: Account<'info, WrapperConfig>,
+
+    pub pending_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, WrapperConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Config in the layout before `pending_admin`, so not deserialized
+    /// CHECK: Discriminator and admin are checked by the instruction
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        owner = crate::ID
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
 
 // ============================================================================
 // State
@@ -329,6 +479,8 @@ pub struct WrapperConfig {
     pub total_wrapped: u64,
     pub paused: bool,
     pub bump: u8,
+    // Appended after the original layout; see `migrate_config`
+    pub pending_admin: Option<Pubkey>,
 }
 
 // ============================================================================
@@ -351,6 +503,27 @@ pub struct WithdrawEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
 // ============================================================================
 // Errors
 // ============================================================================
@@ -363,4 +536,7 @@ pub enum WrapperError {
     Overflow,
     Underflow,
     Unauthorized,
+    NoPendingAdmin,
+    NotPendingAdmin,
+    AlreadyMigrated,
 }
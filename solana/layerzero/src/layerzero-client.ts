@@ -3,7 +3,7 @@
  * Wrapper around @layerzerolabs/lz-solana-sdk-v2
  */
 
-import { Connection, PublicKey, Keypair } from "@solana/web3.js";
+import { Connection, PublicKey, Keypair, AccountMeta, SystemProgram } from "@solana/web3.js";
 import { Program, AnchorProvider, Wallet } from "@coral-xyz/anchor";
 import * as anchor from "@coral-xyz/anchor";
 import { EagleOftLayerzero } from "../target/types/eagle_oft_layerzero";
@@ -12,7 +12,6 @@ import {
   getEndpointForCluster,
   getSolanaEid,
   OftMessage,
-  FeeEstimator,
   DecimalConverter,
   ethereumAddressToBytes32,
 } from "./layerzero-config";
@@ -23,6 +22,7 @@ export interface SendParams {
   amountLD: bigint; // Amount in local decimals (9 for Solana)
   minAmountLD: bigint; // Minimum amount with slippage
   extraOptions?: Uint8Array;
+  composeMsg?: Uint8Array; // Payload for the destination composer (send-and-call)
 }
 
 export interface SendReceipt {
@@ -42,6 +42,7 @@ export class LayerZeroClient {
   // PDAs
   private oftConfigPda: PublicKey;
   private oftConfigBump: number;
+  private rolesPda: PublicKey;
 
   constructor(
     connection: Connection,
@@ -69,19 +70,26 @@ export class LayerZeroClient {
       [Buffer.from("oft_config")],
       this.program.programId
     );
+    [this.rolesPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("roles")],
+      this.program.programId
+    );
   }
 
   /**
    * Initialize the OFT
+   * `sharedDecimals` must match the EVM OFT's sharedDecimals()
    */
-  async initialize(admin: PublicKey): Promise<string> {
+  async initialize(admin: PublicKey, sharedDecimals = 6): Promise<string> {
     const endpoint = getEndpointForCluster(this.cluster);
+    const localEid = getSolanaEid(this.cluster);
     const mintKeypair = Keypair.generate();
 
     const tx = await this.program.methods
-      .initialize(endpoint, admin)
+      .initialize(endpoint, localEid, admin, sharedDecimals)
       .accounts({
         oftConfig: this.oftConfigPda,
+        roles: this.rolesPda,
         mint: mintKeypair.publicKey,
         payer: this.wallet.publicKey,
       })
@@ -95,6 +103,42 @@ export class LayerZeroClient {
     return tx;
   }
 
+  /**
+   * Register the OFT as an OApp at the LayerZero endpoint (admin only)
+   * Needed once before sends, receives or delegate changes
+   */
+  async registerOApp(): Promise<string> {
+    const config = await this.program.account.oftConfig.fetch(this.oftConfigPda);
+    const endpointPda = (seeds: Buffer[]) =>
+      PublicKey.findProgramAddressSync(seeds, config.endpointProgram)[0];
+
+    const tx = await this.program.methods
+      .registerOapp()
+      .accounts({
+        oftConfig: this.oftConfigPda,
+        admin: this.wallet.publicKey,
+        endpointProgram: config.endpointProgram,
+      })
+      .remainingAccounts([
+        { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+        { pubkey: this.oftConfigPda, isSigner: false, isWritable: false },
+        {
+          pubkey: endpointPda([Buffer.from("OApp"), this.oftConfigPda.toBuffer()]),
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: endpointPda([Buffer.from("__event_authority")]), isSigner: false, isWritable: false },
+        { pubkey: config.endpointProgram, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+    console.log("✅ Registered with LayerZero endpoint");
+    console.log("   Transaction:", tx);
+
+    return tx;
+  }
+
   /**
    * Set peer OFT on another chain
    */
@@ -111,8 +155,9 @@ export class LayerZeroClient {
       .setPeer(dstEid, peerBytes32Array)
       .accounts({
         oftConfig: this.oftConfigPda,
+        roles: this.rolesPda,
         peerConfig: peerConfigPda,
-        admin: this.wallet.publicKey,
+        authority: this.wallet.publicKey,
       })
       .rpc();
 
@@ -123,18 +168,95 @@ export class LayerZeroClient {
     return tx;
   }
 
+  /**
+   * Point an existing peer at a new OFT address (admin only)
+   */
+  async setPeerAddress(dstEid: number, peerAddress: string): Promise<string> {
+    const peerBytes32Array = Array.from(ethereumAddressToBytes32(peerAddress));
+
+    const tx = await this.program.methods
+      .setPeerAddress(dstEid, peerBytes32Array)
+      .accounts({
+        oftConfig: this.oftConfigPda,
+        roles: this.rolesPda,
+        peerConfig: this.getPeerPda(dstEid),
+        authority: this.wallet.publicKey,
+      })
+      .rpc();
+
+    console.log("🔧 Peer address updated for EID", dstEid);
+    console.log("   Transaction:", tx);
+    console.log("   Peer address:", peerAddress);
+
+    return tx;
+  }
+
+  /**
+   * Close a peer and reclaim its rent (admin only)
+   */
+  async closePeer(dstEid: number): Promise<string> {
+    const tx = await this.program.methods
+      .closePeer(dstEid)
+      .accounts({
+        oftConfig: this.oftConfigPda,
+        roles: this.rolesPda,
+        peerConfig: this.getPeerPda(dstEid),
+        authority: this.wallet.publicKey,
+      })
+      .rpc();
+
+    console.log("🗑️ Peer closed for EID", dstEid);
+    console.log("   Transaction:", tx);
+
+    return tx;
+  }
+
+  /**
+   * Restrict which executors may deliver messages from a peer; an empty
+   * list allows any executor (peer manager only)
+   */
+  async setPeerExecutors(dstEid: number, executors: PublicKey[]): Promise<string> {
+    const tx = await this.program.methods
+      .setPeerExecutors(dstEid, executors)
+      .accounts({
+        oftConfig: this.oftConfigPda,
+        roles: this.rolesPda,
+        peerConfig: this.getPeerPda(dstEid),
+        authority: this.wallet.publicKey,
+      })
+      .rpc();
+
+    console.log("🔧 Executors set for EID", dstEid);
+    console.log("   Transaction:", tx);
+
+    return tx;
+  }
+
+  private getPeerPda(dstEid: number): PublicKey {
+    const [peerConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("peer"), Buffer.from(new Uint32Array([dstEid]).buffer)],
+      this.program.programId
+    );
+    return peerConfigPda;
+  }
+
   /**
    * Send tokens to another chain
+   *
+   * `endpointAccounts` are the LayerZero endpoint `send` accounts (starting
+   * with the OFT config PDA) followed by the send library accounts;
+   * `quoteAccounts` are the matching endpoint `quote` accounts.
    */
-  async send(params: SendParams, fromTokenAccount: PublicKey): Promise<SendReceipt> {
+  async send(
+    params: SendParams,
+    fromTokenAccount: PublicKey,
+    endpointAccounts: AccountMeta[],
+    quoteAccounts: AccountMeta[]
+  ): Promise<SendReceipt> {
     // Get config and mint
     const config = await this.program.account.oftConfig.fetch(this.oftConfigPda);
     
-    // Get peer config PDA
-    const [peerConfigPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("peer"), Buffer.from(new Uint32Array([params.dstEid]).buffer)],
-      this.program.programId
-    );
+    const peerConfigPda = this.getPeerPda(params.dstEid);
 
     // Convert address to bytes32
     const toBytes32 = ethereumAddressToBytes32(params.toAddress);
@@ -147,25 +269,33 @@ export class LayerZeroClient {
       amountLd: new anchor.BN(params.amountLD.toString()),
       minAmountLd: new anchor.BN(params.minAmountLD.toString()),
       extraOptions: params.extraOptions || [],
-      composeMsg: [],
+      composeMsg: params.composeMsg || [],
       oftCmd: [],
     };
 
+    const fee = await this.quoteSend(params, quoteAccounts);
+
     // Send transaction
     const tx = await this.program.methods
-      .send(sendParam)
+      .send(params.dstEid, sendParam, {
+        nativeFee: new anchor.BN(fee.nativeFee),
+        lzTokenFee: new anchor.BN(fee.lzTokenFee),
+      })
       .accounts({
         oftConfig: this.oftConfigPda,
         peerConfig: peerConfigPda,
         mint: config.mint,
+        feeVault: config.feeVault.equals(PublicKey.default) ? null : config.feeVault,
         from: fromTokenAccount,
         sender: this.wallet.publicKey,
         endpointProgram: config.endpointProgram,
+        timelockOperation,
       })
+      .remainingAccounts(endpointAccounts)
       .rpc();
 
-    // Get updated config for nonce
-    const updatedConfig = await this.program.account.oftConfig.fetch(this.oftConfigPda);
+    // Outbound nonce for this destination peer
+    const peer = await this.program.account.peerConfig.fetch(peerConfigPda);
 
     console.log("🚀 Sent cross-chain message");
     console.log("   Transaction:", tx);
@@ -176,19 +306,22 @@ export class LayerZeroClient {
     return {
       signature: tx,
       guid: new Uint8Array(32), // Would come from event
-      nonce: Number(updatedConfig.totalBridgedOut),
-      fee: await FeeEstimator.estimate(
-        getSolanaEid(this.cluster),
-        params.dstEid,
-        41 // OFT message size
-      ),
+      nonce: Number(peer.outboundNonce),
+      fee,
     };
   }
 
   /**
-   * Quote send fee
+   * Quote send fee from the LayerZero endpoint
+   *
+   * `quoteAccounts` are the endpoint `quote` accounts followed by the send
+   * library accounts.
    */
-  async quoteSend(params: SendParams): Promise<{ nativeFee: number; lzTokenFee: number }> {
+  async quoteSend(
+    params: SendParams,
+    quoteAccounts: AccountMeta[],
+    payInLzToken = false
+  ): Promise<{ nativeFee: number; lzTokenFee: number }> {
     const toBytes32Array = Array.from(ethereumAddressToBytes32(params.toAddress));
 
     const sendParam = {
@@ -197,18 +330,26 @@ export class LayerZeroClient {
       amountLd: new anchor.BN(params.amountLD.toString()),
       minAmountLd: new anchor.BN(params.minAmountLD.toString()),
       extraOptions: params.extraOptions || [],
-      composeMsg: [],
+      composeMsg: params.composeMsg || [],
       oftCmd: [],
     };
 
     const config = await this.program.account.oftConfig.fetch(this.oftConfigPda);
 
+    const [peerConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("peer"), Buffer.from(new Uint32Array([params.dstEid]).buffer)],
+      this.program.programId
+    );
+
     const result = await this.program.methods
-      .quoteSend(sendParam, false)
+      .quoteSend(sendParam, payInLzToken)
       .accounts({
         oftConfig: this.oftConfigPda,
+        peerConfig: peerConfigPda,
+        mint: config.mint,
         endpointProgram: config.endpointProgram,
       })
+      .remainingAccounts(quoteAccounts)
       .view();
 
     return {
@@ -217,6 +358,53 @@ export class LayerZeroClient {
     };
   }
 
+  /**
+   * Preview a transfer: sendable limits, itemized fees and the amounts
+   * debited on Solana and received on the destination
+   *
+   * The limits are for the wallet sending from `fromTokenAccount`; without
+   * one (e.g. before it is created) only the rate limit bounds them.
+   */
+  async quoteOft(params: SendParams, fromTokenAccount: PublicKey | null = null) {
+    const sendParam = {
+      dstEid: params.dstEid,
+      to: Array.from(ethereumAddressToBytes32(params.toAddress)),
+      amountLd: new anchor.BN(params.amountLD.toString()),
+      minAmountLd: new anchor.BN(params.minAmountLD.toString()),
+      extraOptions: params.extraOptions || [],
+      composeMsg: params.composeMsg || [],
+      oftCmd: [],
+    };
+
+    const config = await this.program.account.oftConfig.fetch(this.oftConfigPda);
+
+    const quote = await this.program.methods
+      .quoteOft(sendParam)
+      .accounts({
+        oftConfig: this.oftConfigPda,
+        peerConfig: this.getPeerPda(params.dstEid),
+        mint: config.mint,
+        from: fromTokenAccount,
+        sender: this.wallet.publicKey,
+      })
+      .view();
+
+    return {
+      limit: {
+        minAmountLD: BigInt(quote.limit.minAmountLd.toString()),
+        maxAmountLD: BigInt(quote.limit.maxAmountLd.toString()),
+      },
+      feeDetails: quote.feeDetails.map((detail: any) => ({
+        feeAmountLD: BigInt(detail.feeAmountLd.toString()),
+        description: detail.description as string,
+      })),
+      receipt: {
+        amountSentLD: BigInt(quote.receipt.amountSentLd.toString()),
+        amountReceivedLD: BigInt(quote.receipt.amountReceivedLd.toString()),
+      },
+    };
+  }
+
   /**
    * Get OFT configuration
    */
@@ -244,7 +432,8 @@ export class LayerZeroClient {
       .setPaused(paused)
       .accounts({
         oftConfig: this.oftConfigPda,
-        admin: this.wallet.publicKey,
+        roles: this.rolesPda,
+        authority: this.wallet.publicKey,
       })
       .rpc();
 
@@ -254,6 +443,70 @@ export class LayerZeroClient {
     return tx;
   }
 
+  /**
+   * Grant or revoke an operational role (admin only)
+   *
+   * `endpointAccounts` are the endpoint `set_delegate` accounts, needed only
+   * for the delegate role. `timelockOperation` is the matured operation for
+   * this change, required while a timelock delay is set.
+   */
+  async setRole(
+    role:
+      | "pauser"
+      | "peerManager"
+      | "rateLimitManager"
+      | "feeManager"
+      | "delegate"
+      | "compliance",
+    account: PublicKey,
+    granted: boolean,
+    endpointAccounts: AccountMeta[] = [],
+    timelockOperation: PublicKey | null = null
+  ): Promise<string> {
+    const config = await this.program.account.oftConfig.fetch(this.oftConfigPda);
+    const method = granted ? this.program.methods.grantRole : this.program.methods.revokeRole;
+
+    const tx = await method({ [role]: {} } as any, account)
+      .accounts({
+        oftConfig: this.oftConfigPda,
+        roles: this.rolesPda,
+        admin: this.wallet.publicKey,
+        endpointProgram: config.endpointProgram,
+        timelockOperation,
+      })
+      .remainingAccounts(endpointAccounts)
+      .rpc();
+
+    console.log(`🔑 ${granted ? "Granted" : "Revoked"} ${role} for ${account.toBase58()}`);
+    console.log("   Transaction:", tx);
+
+    return tx;
+  }
+
+  /**
+   * Block or unblock a Solana pubkey or an EVM address (compliance only)
+   */
+  async setBlocked(address: PublicKey | string, blocked: boolean): Promise<string> {
+    const bytes32 =
+      typeof address === "string"
+        ? Array.from(ethereumAddressToBytes32(address))
+        : Array.from(address.toBytes());
+    const method = blocked ? this.program.methods.blockAddress : this.program.methods.unblockAddress;
+
+    const tx = await method(bytes32)
+      .accounts({
+        oftConfig: this.oftConfigPda,
+        roles: this.rolesPda,
+        authority: this.wallet.publicKey,
+      })
+      .rpc();
+
+    console.log(`🚫 ${blocked ? "Blocked" : "Unblocked"} ${address.toString()}`);
+    console.log("   Transaction:", tx);
+
+    return tx;
+  }
+
   /**
    * Get bridge statistics
    */
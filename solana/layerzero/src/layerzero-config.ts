@@ -145,28 +145,43 @@ export const DEFAULT_EXECUTOR_CONFIG: ExecutorConfig = {
 
 /**
  * Message encoding/decoding utilities
+ * Matches LayerZero's OFTMsgCodec:
+ * sendTo (bytes32) | amountSD (uint64 BE) | [composeFrom (bytes32) | composeMsg]
  */
 export class OftMessage {
-  static encode(msgType: number, to: Uint8Array, amount: bigint): Uint8Array {
-    const buffer = new Uint8Array(1 + 32 + 8);
-    buffer[0] = msgType;
-    buffer.set(to, 1);
+  static encode(
+    to: Uint8Array,
+    amountSD: bigint,
+    composeFrom?: Uint8Array,
+    composeMsg?: Uint8Array
+  ): Uint8Array {
+    const composed = composeMsg !== undefined && composeMsg.length > 0;
+    const buffer = new Uint8Array(40 + (composed ? 32 + composeMsg!.length : 0));
+    buffer.set(to, 0);
     const view = new DataView(buffer.buffer);
-    view.setBigUint64(33, amount, false); // big-endian
+    view.setBigUint64(32, amountSD, false); // big-endian
+    if (composed) {
+      buffer.set(composeFrom ?? new Uint8Array(32), 40);
+      buffer.set(composeMsg!, 72);
+    }
     return buffer;
   }
 
-  static decode(message: Uint8Array): { msgType: number; to: Uint8Array; amount: bigint } {
-    if (message.length < 41) {
+  static decode(message: Uint8Array): {
+    to: Uint8Array;
+    amountSD: bigint;
+    composeMsg?: Uint8Array;
+  } {
+    if (message.length < 40) {
       throw new Error("Invalid message length");
     }
     
-    const msgType = message[0];
-    const to = message.slice(1, 33);
+    const to = message.slice(0, 32);
     const view = new DataView(message.buffer, message.byteOffset);
-    const amount = view.getBigUint64(33, false); // big-endian
+    const amountSD = view.getBigUint64(32, false); // big-endian
+    const composeMsg = message.length > 40 ? message.slice(40) : undefined;
     
-    return { msgType, to, amount };
+    return { to, amountSD, composeMsg };
   }
 }
 
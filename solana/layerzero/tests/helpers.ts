@@ -0,0 +1,332 @@
+/**
+ * Shared fixture for the EAGLE OFT tests: the mock LayerZero endpoint
+ * (loaded at genesis, see Anchor.toml) and an adapter OFT over a fresh mint
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  AccountMeta,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { EagleOftLayerzero } from "../target/types/eagle_oft_layerzero";
+import { LAYERZERO_EIDS, ethereumAddressToBytes32 } from "../src/layerzero-config";
+
+export const MOCK_ENDPOINT_ID = new PublicKey("Bmba7mZCgWmKnmPCo9cxx5hdJyt7LM6e34GV3CzHsqMS");
+export const LOCAL_EID = LAYERZERO_EIDS.SOLANA_DEVNET;
+
+// Mock endpoint fee: MOCK_NATIVE_FEE + MOCK_FEE_PER_BYTE per message byte
+export const MOCK_NATIVE_FEE = 10_000;
+export const MOCK_FEE_PER_BYTE = 100;
+
+export const DECIMALS = 9;
+export const SHARED_DECIMALS = 6;
+
+export interface OftFixture {
+  provider: anchor.AnchorProvider;
+  program: Program<EagleOftLayerzero>;
+  payer: Keypair;
+  mint: PublicKey;
+  from: PublicKey;
+  oftConfig: PublicKey;
+  escrow: PublicKey;
+}
+
+export interface SendResult {
+  signature: string;
+  receipt: { guid: number[]; nonce: bigint; fee: { nativeFee: bigint; lzTokenFee: bigint } };
+  quotedFee: bigint;
+}
+
+const endpointPda = (seeds: Buffer[]) =>
+  PublicKey.findProgramAddressSync(seeds, MOCK_ENDPOINT_ID)[0];
+
+export const endpointSettings = endpointPda([Buffer.from("Endpoint")]);
+
+const u32be = (value: number) => {
+  const buf = Buffer.alloc(4);
+  buf.writeUInt32BE(value);
+  return buf;
+};
+
+const u64be = (value: bigint) => {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64BE(value);
+  return buf;
+};
+
+let fixture: Promise<OftFixture> | undefined;
+
+/**
+ * The OFT config is a singleton PDA, so every test file shares one OFT
+ */
+export function oftFixture(): Promise<OftFixture> {
+  fixture ??= createFixture();
+  return fixture;
+}
+
+async function createFixture(): Promise<OftFixture> {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.EagleOftLayerzero as Program<EagleOftLayerzero>;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  await initMockEndpoint(provider, payer);
+
+  const mint = await createMint(provider.connection, payer, payer.publicKey, null, DECIMALS);
+  const from = (
+    await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, payer.publicKey)
+  ).address;
+  await mintTo(provider.connection, payer, mint, from, payer, 1_000_000n * 10n ** BigInt(DECIMALS));
+
+  await program.methods
+    .initializeAdapter(MOCK_ENDPOINT_ID, LOCAL_EID, payer.publicKey, SHARED_DECIMALS)
+    .accountsPartial({
+      mint,
+      payer: payer.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc();
+
+  const [oftConfig] = PublicKey.findProgramAddressSync([Buffer.from("oft_config")], program.programId);
+  const [escrow] = PublicKey.findProgramAddressSync([Buffer.from("escrow")], program.programId);
+
+  await program.methods
+    .registerOapp()
+    .accountsPartial({
+      oftConfig,
+      admin: payer.publicKey,
+      endpointProgram: MOCK_ENDPOINT_ID,
+    })
+    .remainingAccounts(registerOAppAccounts(oftConfig, payer.publicKey))
+    .rpc();
+
+  return { provider, program, payer, mint, from, oftConfig, escrow };
+}
+
+/**
+ * Create the mock endpoint's settings. The mock has no IDL in the
+ * workspace, so the instruction is built by hand.
+ */
+async function initMockEndpoint(provider: anchor.AnchorProvider, payer: Keypair) {
+  const discriminator = Buffer.from(
+    anchor.utils.sha256.hash("global:init_endpoint"),
+    "hex"
+  ).subarray(0, 8);
+  const args = Buffer.alloc(20);
+  args.writeUInt32LE(LOCAL_EID, 0);
+  args.writeBigUInt64LE(BigInt(MOCK_NATIVE_FEE), 4);
+  args.writeBigUInt64LE(BigInt(MOCK_FEE_PER_BYTE), 12);
+
+  const ix = new TransactionInstruction({
+    programId: MOCK_ENDPOINT_ID,
+    keys: [
+      { pubkey: endpointSettings, isSigner: false, isWritable: true },
+      { pubkey: payer.publicKey, isSigner: true, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data: Buffer.concat([discriminator, args]),
+  });
+
+  await provider.sendAndConfirm(new Transaction().add(ix));
+}
+
+export function peerPda(program: Program<EagleOftLayerzero>, eid: number): PublicKey {
+  const seed = Buffer.alloc(4);
+  seed.writeUInt32LE(eid);
+  return PublicKey.findProgramAddressSync([Buffer.from("peer"), seed], program.programId)[0];
+}
+
+export function blockedPda(program: Program<EagleOftLayerzero>, address: Buffer): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("blocked"), address], program.programId)[0];
+}
+
+export function peerAddress(evmAddress: string): number[] {
+  return Array.from(ethereumAddressToBytes32(evmAddress));
+}
+
+export function oappRegistry(oftConfig: PublicKey): PublicKey {
+  return endpointPda([Buffer.from("OApp"), oftConfig.toBuffer()]);
+}
+
+export function outboundNonce(oftConfig: PublicKey, dstEid: number, receiver: number[]): PublicKey {
+  return endpointPda([
+    Buffer.from("Nonce"),
+    oftConfig.toBuffer(),
+    u32be(dstEid),
+    Buffer.from(receiver),
+  ]);
+}
+
+/** Mock endpoint `register_oapp` accounts */
+export function registerOAppAccounts(oftConfig: PublicKey, payer: PublicKey): AccountMeta[] {
+  return [
+    { pubkey: payer, isSigner: true, isWritable: true },
+    { pubkey: oftConfig, isSigner: false, isWritable: false },
+    { pubkey: oappRegistry(oftConfig), isSigner: false, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    { pubkey: endpointPda([Buffer.from("__event_authority")]), isSigner: false, isWritable: false },
+    { pubkey: MOCK_ENDPOINT_ID, isSigner: false, isWritable: false },
+  ];
+}
+
+/**
+ * Mock endpoint `send` accounts: the seven endpoint accounts (OApp first),
+ * then the payer and system program standing in for the send library's
+ */
+export function sendAccounts(
+  oftConfig: PublicKey,
+  dstEid: number,
+  receiver: number[],
+  payer: PublicKey
+): AccountMeta[] {
+  const unused = { pubkey: MOCK_ENDPOINT_ID, isSigner: false, isWritable: false };
+  return [
+    { pubkey: oftConfig, isSigner: false, isWritable: false },
+    unused,
+    unused,
+    unused,
+    unused,
+    { pubkey: endpointSettings, isSigner: false, isWritable: true },
+    { pubkey: outboundNonce(oftConfig, dstEid, receiver), isSigner: false, isWritable: true },
+    { pubkey: payer, isSigner: true, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+}
+
+/** Mock endpoint `quote` accounts */
+export function quoteAccounts(oftConfig: PublicKey, dstEid: number, receiver: number[]): AccountMeta[] {
+  const unused = { pubkey: MOCK_ENDPOINT_ID, isSigner: false, isWritable: false };
+  return [
+    unused,
+    unused,
+    unused,
+    unused,
+    { pubkey: endpointSettings, isSigner: false, isWritable: false },
+    { pubkey: outboundNonce(oftConfig, dstEid, receiver), isSigner: false, isWritable: false },
+  ];
+}
+
+/** GUID as assigned by the LayerZero endpoint */
+export function expectedGuid(
+  nonce: bigint,
+  srcEid: number,
+  sender: PublicKey,
+  dstEid: number,
+  receiver: number[]
+): number[] {
+  return Array.from(
+    keccak_256(
+      Buffer.concat([
+        u64be(nonce),
+        u32be(srcEid),
+        sender.toBuffer(),
+        u32be(dstEid),
+        Buffer.from(receiver),
+      ])
+    )
+  );
+}
+
+export async function setPeer(f: OftFixture, eid: number, address: number[]) {
+  await f.program.methods
+    .setPeer(eid, address)
+    .accountsPartial({
+      oftConfig: f.oftConfig,
+      peerConfig: peerPda(f.program, eid),
+      authority: f.payer.publicKey,
+      timelockOperation: null,
+    })
+    .rpc();
+}
+
+/**
+ * Quote and send `amountLd` to `dstEid`, reading the `SendReceipt` back
+ * from the transaction's return data. `sendParamEid` overrides
+ * `send_param.dst_eid` and `nativeFee` skips the quote.
+ */
+export async function sendTokens(
+  f: OftFixture,
+  dstEid: number,
+  receiver: number[],
+  amountLd: bigint,
+  overrides: { sendParamEid?: number; nativeFee?: bigint } = {}
+): Promise<SendResult> {
+  const sendParam = (eid: number) => ({
+    dstEid: eid,
+    to: peerAddress("0x000000000000000000000000000000000000dEaD"),
+    amountLd: new anchor.BN(amountLd.toString()),
+    minAmountLd: new anchor.BN(0),
+    extraOptions: Buffer.alloc(0),
+    composeMsg: Buffer.alloc(0),
+    oftCmd: Buffer.alloc(0),
+  });
+  const peerConfig = peerPda(f.program, dstEid);
+
+  const quote =
+    overrides.nativeFee === undefined
+      ? await f.program.methods
+          .quoteSend(sendParam(dstEid), false)
+          .accountsPartial({
+            oftConfig: f.oftConfig,
+            peerConfig,
+            mint: f.mint,
+            endpointProgram: MOCK_ENDPOINT_ID,
+          })
+          .remainingAccounts(quoteAccounts(f.oftConfig, dstEid, receiver))
+          .view()
+      : { nativeFee: new anchor.BN(overrides.nativeFee.toString()), lzTokenFee: new anchor.BN(0) };
+
+  const signature = await f.program.methods
+    .send(dstEid, sendParam(overrides.sendParamEid ?? dstEid), quote)
+    .accountsPartial({
+      oftConfig: f.oftConfig,
+      peerConfig,
+      mint: f.mint,
+      escrow: f.escrow,
+      feeVault: null,
+      from: f.from,
+      sender: f.payer.publicKey,
+      senderBlocked: blockedPda(f.program, f.payer.publicKey.toBuffer()),
+      recipientBlocked: blockedPda(f.program, Buffer.from(sendParam(dstEid).to)),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      endpointProgram: MOCK_ENDPOINT_ID,
+    })
+    .remainingAccounts(sendAccounts(f.oftConfig, dstEid, receiver, f.payer.publicKey))
+    .rpc({ commitment: "confirmed" });
+
+  const tx = await f.provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const returnData = tx?.meta?.returnData;
+  if (!returnData || returnData.programId !== f.program.programId.toBase58()) {
+    throw new Error("send returned no receipt");
+  }
+  // SendReceipt: guid (32) | nonce (u64) | native_fee (u64) | lz_token_fee (u64)
+  const data = Buffer.from(returnData.data[0], "base64");
+
+  return {
+    signature,
+    receipt: {
+      guid: Array.from(data.subarray(0, 32)),
+      nonce: data.readBigUInt64LE(32),
+      fee: {
+        nativeFee: data.readBigUInt64LE(40),
+        lzTokenFee: data.readBigUInt64LE(48),
+      },
+    },
+    quotedFee: BigInt(quote.nativeFee.toString()),
+  };
+}
@@ -0,0 +1,130 @@
+/**
+ * Peer routing: sends reach the endpoint only for configured, enabled
+ * peers, each with its own EID, receiver address and nonce path
+ */
+
+import { expect } from "chai";
+import { AnchorError } from "@coral-xyz/anchor";
+import { LAYERZERO_EIDS } from "../src/layerzero-config";
+import {
+  LOCAL_EID,
+  OftFixture,
+  expectedGuid,
+  oftFixture,
+  outboundNonce,
+  peerAddress,
+  peerPda,
+  sendTokens,
+  setPeer,
+} from "./helpers";
+
+async function expectAnchorError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (err) {
+    expect(err).to.be.instanceOf(AnchorError);
+    expect((err as AnchorError).error.errorCode.code).to.equal(code);
+    return;
+  }
+  expect.fail(`expected ${code}`);
+}
+
+describe("peer routing", () => {
+  const peers = [
+    { eid: LAYERZERO_EIDS.ARBITRUM, address: peerAddress("0x2222222222222222222222222222222222222222") },
+    { eid: LAYERZERO_EIDS.BASE, address: peerAddress("0x3333333333333333333333333333333333333333") },
+  ];
+  const disabled = {
+    eid: LAYERZERO_EIDS.OPTIMISM,
+    address: peerAddress("0x4444444444444444444444444444444444444444"),
+  };
+  const unconfiguredEid = LAYERZERO_EIDS.POLYGON;
+  let f: OftFixture;
+
+  const setPeerEnabled = (eid: number, enabled: boolean) =>
+    f.program.methods
+      .setPeerEnabled(eid, enabled)
+      .accountsPartial({
+        oftConfig: f.oftConfig,
+        peerConfig: peerPda(f.program, eid),
+        authority: f.payer.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    f = await oftFixture();
+    for (const peer of [...peers, disabled]) {
+      await setPeer(f, peer.eid, peer.address);
+    }
+    await setPeerEnabled(disabled.eid, false);
+  });
+
+  it("fails for an EID without a peer", async () => {
+    const address = peerAddress("0x5555555555555555555555555555555555555555");
+
+    await expectAnchorError(
+      sendTokens(f, unconfiguredEid, address, 1_000_000_000n, { nativeFee: 1_000_000n }),
+      "AccountNotInitialized"
+    );
+    const peerConfig = await f.provider.connection.getAccountInfo(peerPda(f.program, unconfiguredEid));
+    expect(peerConfig).to.be.null;
+  });
+
+  it("fails for a disabled peer, in quote and send", async () => {
+    await expectAnchorError(
+      sendTokens(f, disabled.eid, disabled.address, 1_000_000_000n),
+      "PeerDisabled"
+    );
+    await expectAnchorError(
+      sendTokens(f, disabled.eid, disabled.address, 1_000_000_000n, { nativeFee: 1_000_000n }),
+      "PeerDisabled"
+    );
+    // Nothing reached the endpoint
+    const nonce = await f.provider.connection.getAccountInfo(
+      outboundNonce(f.oftConfig, disabled.eid, disabled.address)
+    );
+    expect(nonce).to.be.null;
+  });
+
+  it("fails when the peer account does not match send_param.dst_eid", async () => {
+    await expectAnchorError(
+      sendTokens(f, peers[0].eid, peers[0].address, 1_000_000_000n, {
+        sendParamEid: peers[1].eid,
+        nativeFee: 1_000_000n,
+      }),
+      "InvalidPeer"
+    );
+  });
+
+  it("routes each send to its peer's EID and address", async () => {
+    for (const peer of peers) {
+      const { receipt } = await sendTokens(f, peer.eid, peer.address, 1_000_000_000n);
+
+      // The endpoint derives the GUID from the destination EID and receiver
+      expect(receipt.nonce).to.equal(1n);
+      expect(receipt.guid).to.deep.equal(
+        expectedGuid(1n, LOCAL_EID, f.oftConfig, peer.eid, peer.address)
+      );
+
+      const peerConfig = await f.program.account.peerConfig.fetch(peerPda(f.program, peer.eid));
+      expect(peerConfig.outboundNonce.toString()).to.equal("1");
+    }
+
+    // One more to the first peer advances only its path
+    const { receipt } = await sendTokens(f, peers[0].eid, peers[0].address, 1_000_000_000n);
+    expect(receipt.nonce).to.equal(2n);
+    const second = await f.program.account.peerConfig.fetch(peerPda(f.program, peers[1].eid));
+    expect(second.outboundNonce.toString()).to.equal("1");
+  });
+
+  it("routes again once a disabled peer is re-enabled", async () => {
+    await setPeerEnabled(disabled.eid, true);
+
+    const { receipt } = await sendTokens(f, disabled.eid, disabled.address, 1_000_000_000n);
+
+    expect(receipt.nonce).to.equal(1n);
+    expect(receipt.guid).to.deep.equal(
+      expectedGuid(1n, LOCAL_EID, f.oftConfig, disabled.eid, disabled.address)
+    );
+  });
+});
@@ -0,0 +1,81 @@
+/**
+ * `send` against the mock endpoint: exercises the hand-built endpoint CPI
+ * (discriminators, params, OApp signer and return data) end to end
+ */
+
+import { expect } from "chai";
+import { getAccount } from "@solana/spl-token";
+import { LAYERZERO_EIDS } from "../src/layerzero-config";
+import {
+  LOCAL_EID,
+  MOCK_ENDPOINT_ID,
+  MOCK_FEE_PER_BYTE,
+  MOCK_NATIVE_FEE,
+  OftFixture,
+  endpointSettings,
+  expectedGuid,
+  oappRegistry,
+  oftFixture,
+  peerAddress,
+  peerPda,
+  sendTokens,
+  setPeer,
+} from "./helpers";
+
+describe("send", () => {
+  const dstEid = LAYERZERO_EIDS.ETHEREUM_SEPOLIA;
+  const peer = peerAddress("0x1111111111111111111111111111111111111111");
+  // Plain sends carry sendTo (32) and amountSD (8)
+  const expectedFee = BigInt(MOCK_NATIVE_FEE + MOCK_FEE_PER_BYTE * 40);
+  let f: OftFixture;
+
+  before(async () => {
+    f = await oftFixture();
+    await setPeer(f, dstEid, peer);
+  });
+
+  it("is registered as an OApp at the endpoint", async () => {
+    const registry = await f.provider.connection.getAccountInfo(oappRegistry(f.oftConfig));
+
+    expect(registry?.owner.equals(MOCK_ENDPOINT_ID)).to.be.true;
+  });
+
+  it("returns the nonce, GUID and fee assigned by the endpoint", async () => {
+    const endpointBefore = await f.provider.connection.getBalance(endpointSettings);
+    const escrowBefore = (await getAccount(f.provider.connection, f.escrow)).amount;
+
+    const { receipt, quotedFee } = await sendTokens(f, dstEid, peer, 5_000_000_000n);
+
+    expect(receipt.nonce).to.equal(1n);
+    expect(receipt.guid).to.deep.equal(expectedGuid(1n, LOCAL_EID, f.oftConfig, dstEid, peer));
+    expect(quotedFee).to.equal(expectedFee);
+    expect(receipt.fee.nativeFee).to.equal(expectedFee);
+    expect(receipt.fee.lzTokenFee).to.equal(0n);
+
+    // The endpoint collected the fee and the tokens are locked
+    const endpointAfter = await f.provider.connection.getBalance(endpointSettings);
+    expect(BigInt(endpointAfter - endpointBefore)).to.equal(expectedFee);
+    const escrowAfter = (await getAccount(f.provider.connection, f.escrow)).amount;
+    expect(escrowAfter - escrowBefore).to.equal(5_000_000_000n);
+  });
+
+  it("advances the nonce and records it on the peer", async () => {
+    const { receipt } = await sendTokens(f, dstEid, peer, 1_000_000_000n);
+
+    expect(receipt.nonce).to.equal(2n);
+    expect(receipt.guid).to.deep.equal(expectedGuid(2n, LOCAL_EID, f.oftConfig, dstEid, peer));
+
+    const peerConfig = await f.program.account.peerConfig.fetch(peerPda(f.program, dstEid));
+    expect(peerConfig.outboundNonce.toString()).to.equal("2");
+  });
+
+  it("removes dust before sending", async () => {
+    const escrowBefore = (await getAccount(f.provider.connection, f.escrow)).amount;
+
+    // 9 local decimals, 6 shared: the last three digits are dust
+    await sendTokens(f, dstEid, peer, 1_000_000_999n);
+
+    const escrowAfter = (await getAccount(f.provider.connection, f.escrow)).amount;
+    expect(escrowAfter - escrowBefore).to.equal(1_000_000_000n);
+  });
+});
@@ -0,0 +1,58 @@
+//! Payload handed to the endpoint's compose queue for inbound send-and-call
+//! messages, matching the LayerZero Solana OFT `compose_msg_codec`.
+//!
+//! Layout:
+//! `nonce (u64) | src_eid (u32) | amount_ld (u64) | composeFrom (bytes32) | composeMsg`,
+//! integers big-endian.
+
+/// Encode the compose payload for a received message. `compose_msg` is the
+/// `composeFrom | composeMsg` tail of the OFT message.
+pub fn encode(nonce: u64, src_eid: u32, amount_ld: u64, compose_msg: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(20 + compose_msg.len());
+    encoded.extend_from_slice(&nonce.to_be_bytes());
+    encoded.extend_from_slice(&src_eid.to_be_bytes());
+    encoded.extend_from_slice(&amount_ld.to_be_bytes());
+    encoded.extend_from_slice(compose_msg);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSE_FROM: &str = "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+    /// `encode(7, 30101, 1_000_000_000, COMPOSE_FROM | 0xdeadbeef)`
+    const COMPOSED: &str = concat!(
+        "0000000000000007",
+        "00007595",
+        "000000003b9aca00",
+        "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8",
+        "deadbeef",
+    );
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn encodes_compose_payload() {
+        let compose_msg = [hex(COMPOSE_FROM), hex("deadbeef")].concat();
+
+        assert_eq!(encode(7, 30101, 1_000_000_000, &compose_msg), hex(COMPOSED));
+    }
+
+    #[test]
+    fn places_fields_at_fixed_offsets() {
+        let encoded = encode(u64::MAX, u32::MAX, 1, &hex(COMPOSE_FROM));
+
+        assert_eq!(encoded.len(), 20 + 32);
+        assert_eq!(encoded[..8], [0xff; 8]);
+        assert_eq!(encoded[8..12], [0xff; 4]);
+        assert_eq!(encoded[12..20], 1u64.to_be_bytes());
+        assert_eq!(encoded[20..], hex(COMPOSE_FROM)[..]);
+    }
+}
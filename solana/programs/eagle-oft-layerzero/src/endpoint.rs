@@ -0,0 +1,660 @@
+//! Minimal CPI bindings for the LayerZero V2 endpoint program.
+//!
+//! The LayerZero Solana SDK is not a dependency of this crate, so the
+//! endpoint instructions we need are built by hand: an 8-byte Anchor
+//! discriminator followed by the Borsh-encoded params. Results are read
+//! back from the endpoint's return data.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke_signed};
+
+use crate::{MessagingFee, OftError};
+
+/// sha256("global:send")[..8]
+const SEND_DISCRIMINATOR: [u8; 8] = [102, 251, 20, 187, 65, 75, 12, 69];
+/// sha256("global:quote")[..8]
+const QUOTE_DISCRIMINATOR: [u8; 8] = [149, 42, 109, 247, 134, 146, 213, 123];
+/// sha256("global:clear")[..8]
+const CLEAR_DISCRIMINATOR: [u8; 8] = [250, 39, 28, 213, 123, 163, 133, 5];
+/// sha256("global:send_compose")[..8]
+const SEND_COMPOSE_DISCRIMINATOR: [u8; 8] = [75, 38, 228, 168, 43, 39, 238, 229];
+/// sha256("global:set_delegate")[..8]
+const SET_DELEGATE_DISCRIMINATOR: [u8; 8] = [242, 30, 46, 76, 108, 235, 128, 181];
+/// sha256("global:register_oapp")[..8]
+const REGISTER_OAPP_DISCRIMINATOR: [u8; 8] = [129, 89, 71, 68, 11, 82, 210, 125];
+
+/// Accounts the endpoint's `send` instruction expects before the send
+/// library's own accounts: sender (the OApp), send_library_program,
+/// send_library_config, default_send_library_config, send_library_info,
+/// endpoint settings and the outbound nonce PDA.
+pub const SEND_ACCOUNTS_LEN: usize = 7;
+
+/// Accounts the endpoint's `quote` instruction expects before the send
+/// library's own accounts: the same as `send` minus the sender.
+pub const QUOTE_ACCOUNTS_LEN: usize = 6;
+
+/// Accounts of the endpoint's `clear` instruction: signer (the OApp),
+/// oapp_registry, nonce, payload_hash, endpoint settings, event_authority
+/// and the endpoint program itself.
+pub const CLEAR_ACCOUNTS_LEN: usize = 7;
+
+/// Accounts of the endpoint's `send_compose` instruction: from (the OApp),
+/// payer, compose_message PDA, system_program, event_authority and the
+/// endpoint program itself.
+pub const SEND_COMPOSE_ACCOUNTS_LEN: usize = 6;
+
+/// Accounts of the endpoint's `set_delegate` instruction: oapp,
+/// oapp_registry, event_authority and the endpoint program itself.
+pub const SET_DELEGATE_ACCOUNTS_LEN: usize = 4;
+
+/// Accounts of the endpoint's `register_oapp` instruction: payer, oapp,
+/// oapp_registry, system_program, event_authority and the endpoint program
+/// itself.
+pub const REGISTER_OAPP_ACCOUNTS_LEN: usize = 6;
+
+/// Seeds of the endpoint's PDAs
+pub const ENDPOINT_SEED: &[u8] = b"Endpoint";
+pub const OAPP_SEED: &[u8] = b"OApp";
+pub const NONCE_SEED: &[u8] = b"Nonce";
+pub const PAYLOAD_HASH_SEED: &[u8] = b"PayloadHash";
+pub const COMPOSED_MESSAGE_HASH_SEED: &[u8] = b"ComposedMessage";
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Params of the endpoint's `send` instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EndpointSendParams {
+    pub dst_eid: u32,
+    pub receiver: [u8; 32],
+    pub message: Vec<u8>,
+    pub options: Vec<u8>,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
+
+/// Params of the endpoint's `quote` instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EndpointQuoteParams {
+    pub sender: Pubkey,
+    pub dst_eid: u32,
+    pub receiver: [u8; 32],
+    pub message: Vec<u8>,
+    pub options: Vec<u8>,
+    pub pay_in_lz_token: bool,
+}
+
+/// Params of the endpoint's `clear` instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EndpointClearParams {
+    pub receiver: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub guid: [u8; 32],
+    pub message: Vec<u8>,
+}
+
+/// Params of the endpoint's `send_compose` instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EndpointSendComposeParams {
+    pub to: Pubkey,
+    pub guid: [u8; 32],
+    pub index: u16,
+    pub message: Vec<u8>,
+}
+
+/// Params of the endpoint's `set_delegate` instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EndpointSetDelegateParams {
+    pub delegate: Pubkey,
+}
+
+/// Params of the endpoint's `register_oapp` instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EndpointRegisterOAppParams {
+    pub delegate: Pubkey,
+}
+
+/// Receipt returned by the endpoint's `send` instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MessagingReceipt {
+    pub guid: [u8; 32],
+    pub nonce: u64,
+    pub fee: MessagingFee,
+}
+
+/// Invoke the endpoint's `send` instruction with `oapp` signing via `signer_seeds`.
+///
+/// `accounts` are the endpoint accounts followed by the send library's
+/// accounts, in the order the endpoint expects them. The first one must be
+/// the OApp itself. Any signer among them (e.g. the fee payer) keeps its
+/// signature through the CPI.
+pub fn send<'info>(
+    endpoint_program: &AccountInfo<'info>,
+    oapp: Pubkey,
+    accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[u8]],
+    params: EndpointSendParams,
+) -> Result<MessagingReceipt> {
+    require!(
+        accounts.len() >= SEND_ACCOUNTS_LEN,
+        OftError::InvalidEndpointAccounts
+    );
+    require_keys_eq!(accounts[0].key(), oapp, OftError::InvalidEndpointAccounts);
+
+    invoke_endpoint(
+        endpoint_program,
+        accounts,
+        Some((oapp, signer_seeds)),
+        SEND_DISCRIMINATOR,
+        &params,
+    )?;
+
+    read_return_data(endpoint_program.key)
+}
+
+/// Ask the endpoint what it will charge for `params`.
+///
+/// `accounts` are the endpoint's `quote` accounts followed by the send
+/// library's accounts, as for [`send`].
+pub fn quote<'info>(
+    endpoint_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    params: EndpointQuoteParams,
+) -> Result<MessagingFee> {
+    require!(
+        accounts.len() >= QUOTE_ACCOUNTS_LEN,
+        OftError::InvalidEndpointAccounts
+    );
+
+    invoke_endpoint(endpoint_program, accounts, None, QUOTE_DISCRIMINATOR, &params)?;
+
+    read_return_data(endpoint_program.key)
+}
+
+/// Clear a verified inbound payload at the endpoint, with `oapp` signing
+/// via `signer_seeds`.
+///
+/// Only the first [`CLEAR_ACCOUNTS_LEN`] entries of `accounts` are used,
+/// the first of which must be the OApp itself. Fails unless the endpoint
+/// holds a verified payload hash matching `params`.
+pub fn clear<'info>(
+    endpoint_program: &AccountInfo<'info>,
+    oapp: Pubkey,
+    accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[u8]],
+    params: EndpointClearParams,
+) -> Result<()> {
+    require!(
+        accounts.len() >= CLEAR_ACCOUNTS_LEN,
+        OftError::InvalidEndpointAccounts
+    );
+    require_keys_eq!(accounts[0].key(), oapp, OftError::InvalidEndpointAccounts);
+
+    invoke_endpoint(
+        endpoint_program,
+        &accounts[..CLEAR_ACCOUNTS_LEN],
+        Some((oapp, signer_seeds)),
+        CLEAR_DISCRIMINATOR,
+        &params,
+    )
+}
+
+/// Queue a compose message for `params.to` at the endpoint, with `oapp`
+/// signing via `signer_seeds`.
+///
+/// Only the first [`SEND_COMPOSE_ACCOUNTS_LEN`] entries of `accounts` are
+/// used, the first of which must be the OApp itself.
+pub fn send_compose<'info>(
+    endpoint_program: &AccountInfo<'info>,
+    oapp: Pubkey,
+    accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[u8]],
+    params: EndpointSendComposeParams,
+) -> Result<()> {
+    require!(
+        accounts.len() >= SEND_COMPOSE_ACCOUNTS_LEN,
+        OftError::InvalidEndpointAccounts
+    );
+    require_keys_eq!(accounts[0].key(), oapp, OftError::InvalidEndpointAccounts);
+
+    invoke_endpoint(
+        endpoint_program,
+        &accounts[..SEND_COMPOSE_ACCOUNTS_LEN],
+        Some((oapp, signer_seeds)),
+        SEND_COMPOSE_DISCRIMINATOR,
+        &params,
+    )
+}
+
+/// Set the key allowed to configure the OApp's libraries and DVNs at the
+/// endpoint, with `oapp` signing via `signer_seeds`.
+///
+/// Only the first [`SET_DELEGATE_ACCOUNTS_LEN`] entries of `accounts` are
+/// used, the first of which must be the OApp itself.
+pub fn set_delegate<'info>(
+    endpoint_program: &AccountInfo<'info>,
+    oapp: Pubkey,
+    accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[u8]],
+    params: EndpointSetDelegateParams,
+) -> Result<()> {
+    require!(
+        accounts.len() >= SET_DELEGATE_ACCOUNTS_LEN,
+        OftError::InvalidEndpointAccounts
+    );
+    require_keys_eq!(accounts[0].key(), oapp, OftError::InvalidEndpointAccounts);
+
+    invoke_endpoint(
+        endpoint_program,
+        &accounts[..SET_DELEGATE_ACCOUNTS_LEN],
+        Some((oapp, signer_seeds)),
+        SET_DELEGATE_DISCRIMINATOR,
+        &params,
+    )
+}
+
+/// Create the OApp's registry entry at the endpoint, with `oapp` signing
+/// via `signer_seeds`. `send`, `clear` and `set_delegate` all require it.
+///
+/// Only the first [`REGISTER_OAPP_ACCOUNTS_LEN`] entries of `accounts` are
+/// used; the first is the payer and the second must be the OApp itself.
+pub fn register_oapp<'info>(
+    endpoint_program: &AccountInfo<'info>,
+    oapp: Pubkey,
+    accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[u8]],
+    params: EndpointRegisterOAppParams,
+) -> Result<()> {
+    require!(
+        accounts.len() >= REGISTER_OAPP_ACCOUNTS_LEN,
+        OftError::InvalidEndpointAccounts
+    );
+    require_keys_eq!(accounts[1].key(), oapp, OftError::InvalidEndpointAccounts);
+
+    invoke_endpoint(
+        endpoint_program,
+        &accounts[..REGISTER_OAPP_ACCOUNTS_LEN],
+        Some((oapp, signer_seeds)),
+        REGISTER_OAPP_DISCRIMINATOR,
+        &params,
+    )
+}
+
+/// Accounts of the endpoint's `clear` instruction for an inbound packet,
+/// in order. The OApp signs through CPI, so it is not a transaction signer.
+pub fn clear_accounts(
+    endpoint_program: &Pubkey,
+    oapp: &Pubkey,
+    src_eid: u32,
+    sender: &[u8; 32],
+    nonce: u64,
+) -> [AccountMeta; CLEAR_ACCOUNTS_LEN] {
+    let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, endpoint_program).0;
+    let src_eid = src_eid.to_be_bytes();
+
+    [
+        AccountMeta::new_readonly(*oapp, false),
+        AccountMeta::new_readonly(pda(&[OAPP_SEED, oapp.as_ref()]), false),
+        AccountMeta::new(pda(&[NONCE_SEED, oapp.as_ref(), &src_eid, sender]), false),
+        AccountMeta::new(
+            pda(&[PAYLOAD_HASH_SEED, oapp.as_ref(), &src_eid, sender, &nonce.to_be_bytes()]),
+            false,
+        ),
+        AccountMeta::new(pda(&[ENDPOINT_SEED]), false),
+        AccountMeta::new_readonly(pda(&[EVENT_AUTHORITY_SEED]), false),
+        AccountMeta::new_readonly(*endpoint_program, false),
+    ]
+}
+
+/// Accounts of the endpoint's `send_compose` instruction, in order.
+/// `payer` signs and funds the compose message PDA.
+pub fn send_compose_accounts(
+    endpoint_program: &Pubkey,
+    oapp: &Pubkey,
+    payer: &Pubkey,
+    to: &Pubkey,
+    guid: &[u8; 32],
+    index: u16,
+) -> [AccountMeta; SEND_COMPOSE_ACCOUNTS_LEN] {
+    let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, endpoint_program).0;
+
+    [
+        AccountMeta::new_readonly(*oapp, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(
+            pda(&[
+                COMPOSED_MESSAGE_HASH_SEED,
+                oapp.as_ref(),
+                to.as_ref(),
+                guid,
+                &index.to_be_bytes(),
+            ]),
+            false,
+        ),
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        AccountMeta::new_readonly(pda(&[EVENT_AUTHORITY_SEED]), false),
+        AccountMeta::new_readonly(*endpoint_program, false),
+    ]
+}
+
+fn invoke_endpoint<'info, T: AnchorSerialize>(
+    endpoint_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    signer: Option<(Pubkey, &[&[u8]])>,
+    discriminator: [u8; 8],
+    params: &T,
+) -> Result<()> {
+    let data = instruction_data(discriminator, params)?;
+
+    let metas = account_metas(accounts, signer.map(|(oapp, _)| oapp));
+
+    let mut infos = Vec::with_capacity(accounts.len() + 1);
+    infos.push(endpoint_program.clone());
+    infos.extend_from_slice(accounts);
+
+    let signer_seeds: Vec<&[&[u8]]> = signer.map(|(_, seeds)| vec![seeds]).unwrap_or_default();
+
+    invoke_signed(
+        &Instruction {
+            program_id: endpoint_program.key(),
+            accounts: metas,
+            data,
+        },
+        &infos,
+        &signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// Metas for `accounts` as passed in, with `oapp` also marked as a signer
+fn account_metas(accounts: &[AccountInfo], oapp: Option<Pubkey>) -> Vec<AccountMeta> {
+    accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer || oapp == Some(account.key()),
+            is_writable: account.is_writable,
+        })
+        .collect()
+}
+
+fn instruction_data<T: AnchorSerialize>(discriminator: [u8; 8], params: &T) -> Result<Vec<u8>> {
+    let mut data = discriminator.to_vec();
+    params.serialize(&mut data)?;
+    Ok(data)
+}
+
+fn read_return_data<T: AnchorDeserialize>(endpoint_program: &Pubkey) -> Result<T> {
+    let (program_id, data) = get_return_data().ok_or(OftError::InvalidEndpointResponse)?;
+    require_keys_eq!(program_id, *endpoint_program, OftError::InvalidEndpointResponse);
+
+    T::try_from_slice(&data).map_err(|_| OftError::InvalidEndpointResponse.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::InstructionData;
+
+    // The mock endpoint declares the real endpoint's instructions with the
+    // same names and params, so Anchor derives the bytes the endpoint expects.
+
+    #[test]
+    fn send_data_matches_endpoint() {
+        let params = EndpointSendParams {
+            dst_eid: 30101,
+            receiver: [7u8; 32],
+            message: vec![1, 2, 3],
+            options: vec![0, 3],
+            native_fee: 5_000,
+            lz_token_fee: 0,
+        };
+        let expected = mock_endpoint::instruction::Send {
+            params: mock_endpoint::SendParams {
+                dst_eid: 30101,
+                receiver: [7u8; 32],
+                message: vec![1, 2, 3],
+                options: vec![0, 3],
+                native_fee: 5_000,
+                lz_token_fee: 0,
+            },
+        }
+        .data();
+
+        assert_eq!(instruction_data(SEND_DISCRIMINATOR, &params).unwrap(), expected);
+    }
+
+    #[test]
+    fn quote_data_matches_endpoint() {
+        let sender = Pubkey::new_unique();
+        let params = EndpointQuoteParams {
+            sender,
+            dst_eid: 30101,
+            receiver: [7u8; 32],
+            message: vec![1, 2, 3],
+            options: vec![],
+            pay_in_lz_token: true,
+        };
+        let expected = mock_endpoint::instruction::Quote {
+            params: mock_endpoint::QuoteParams {
+                sender,
+                dst_eid: 30101,
+                receiver: [7u8; 32],
+                message: vec![1, 2, 3],
+                options: vec![],
+                pay_in_lz_token: true,
+            },
+        }
+        .data();
+
+        assert_eq!(instruction_data(QUOTE_DISCRIMINATOR, &params).unwrap(), expected);
+    }
+
+    #[test]
+    fn clear_data_matches_endpoint() {
+        let receiver = Pubkey::new_unique();
+        let params = EndpointClearParams {
+            receiver,
+            src_eid: 30101,
+            sender: [9u8; 32],
+            nonce: 42,
+            guid: [3u8; 32],
+            message: vec![4, 5],
+        };
+        let expected = mock_endpoint::instruction::Clear {
+            params: mock_endpoint::ClearParams {
+                receiver,
+                src_eid: 30101,
+                sender: [9u8; 32],
+                nonce: 42,
+                guid: [3u8; 32],
+                message: vec![4, 5],
+            },
+        }
+        .data();
+
+        assert_eq!(instruction_data(CLEAR_DISCRIMINATOR, &params).unwrap(), expected);
+    }
+
+    #[test]
+    fn send_compose_data_matches_endpoint() {
+        let to = Pubkey::new_unique();
+        let params = EndpointSendComposeParams {
+            to,
+            guid: [3u8; 32],
+            index: 0,
+            message: vec![6],
+        };
+        let expected = mock_endpoint::instruction::SendCompose {
+            params: mock_endpoint::SendComposeParams {
+                to,
+                guid: [3u8; 32],
+                index: 0,
+                message: vec![6],
+            },
+        }
+        .data();
+
+        assert_eq!(
+            instruction_data(SEND_COMPOSE_DISCRIMINATOR, &params).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn set_delegate_data_matches_endpoint() {
+        let delegate = Pubkey::new_unique();
+        let expected = mock_endpoint::instruction::SetDelegate {
+            params: mock_endpoint::SetDelegateParams { delegate },
+        }
+        .data();
+
+        assert_eq!(
+            instruction_data(SET_DELEGATE_DISCRIMINATOR, &EndpointSetDelegateParams { delegate })
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn register_oapp_data_matches_endpoint() {
+        let delegate = Pubkey::new_unique();
+        let expected = mock_endpoint::instruction::RegisterOapp {
+            params: mock_endpoint::RegisterOAppParams { delegate },
+        }
+        .data();
+
+        assert_eq!(
+            instruction_data(
+                REGISTER_OAPP_DISCRIMINATOR,
+                &EndpointRegisterOAppParams { delegate }
+            )
+            .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn receipt_decodes_endpoint_return_data() {
+        let returned = mock_endpoint::MessagingReceipt {
+            guid: [8u8; 32],
+            nonce: 3,
+            fee: mock_endpoint::MessagingFee {
+                native_fee: 1_234,
+                lz_token_fee: 5,
+            },
+        };
+
+        let receipt = MessagingReceipt::try_from_slice(&returned.try_to_vec().unwrap()).unwrap();
+
+        assert_eq!(receipt.guid, [8u8; 32]);
+        assert_eq!(receipt.nonce, 3);
+        assert_eq!(receipt.fee.native_fee, 1_234);
+        assert_eq!(receipt.fee.lz_token_fee, 5);
+    }
+
+    #[test]
+    fn oapp_is_marked_as_signer() {
+        let (oapp, payer, other) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let owner = Pubkey::default();
+        let (mut lamports, mut data) = ([0u64; 3], [[0u8; 0]; 3]);
+        let [l0, l1, l2] = &mut lamports;
+        let [d0, d1, d2] = &mut data;
+        let accounts = [
+            AccountInfo::new(&oapp, false, true, l0, d0, &owner, false, 0),
+            AccountInfo::new(&payer, true, true, l1, d1, &owner, false, 0),
+            AccountInfo::new(&other, false, false, l2, d2, &owner, false, 0),
+        ];
+
+        let flags: Vec<_> = account_metas(&accounts, Some(oapp))
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+            .collect();
+
+        assert_eq!(
+            flags,
+            vec![(oapp, true, true), (payer, true, true), (other, false, false)]
+        );
+        assert!(!account_metas(&accounts, None)[0].is_signer);
+    }
+
+    #[test]
+    fn account_lists_match_endpoint() {
+        let (endpoint_program, oapp, payer) =
+            (mock_endpoint::ID, Pubkey::new_unique(), Pubkey::new_unique());
+        let flags = |metas: &[AccountMeta]| {
+            metas
+                .iter()
+                .map(|meta| (meta.is_signer, meta.is_writable))
+                .collect::<Vec<_>>()
+        };
+
+        let clear = clear_accounts(&endpoint_program, &oapp, 30101, &[9u8; 32], 1);
+        let keys: Vec<_> = clear.iter().map(|meta| meta.pubkey).collect();
+        let mut expected = mock_endpoint::accounts::Clear {
+            signer: keys[0],
+            oapp_registry: keys[1],
+            nonce: keys[2],
+            payload_hash: keys[3],
+            endpoint: keys[4],
+            event_authority: keys[5],
+            program: keys[6],
+        }
+        .to_account_metas(None);
+        // The OApp signs through CPI, not in the transaction
+        expected[0].is_signer = false;
+        assert_eq!(flags(&clear), flags(&expected));
+
+        let compose = send_compose_accounts(&endpoint_program, &oapp, &payer, &oapp, &[3u8; 32], 0);
+        let keys: Vec<_> = compose.iter().map(|meta| meta.pubkey).collect();
+        let mut expected = mock_endpoint::accounts::SendCompose {
+            from: keys[0],
+            payer: keys[1],
+            compose_message: keys[2],
+            system_program: keys[3],
+            event_authority: keys[4],
+            program: keys[5],
+        }
+        .to_account_metas(None);
+        expected[0].is_signer = false;
+        assert_eq!(flags(&compose), flags(&expected));
+
+        let key = Pubkey::new_unique();
+        let send = mock_endpoint::accounts::Send {
+            sender: key,
+            send_library_program: key,
+            send_library_config: key,
+            default_send_library_config: key,
+            send_library_info: key,
+            endpoint: key,
+            nonce: key,
+            payer: key,
+            system_program: key,
+        }
+        .to_account_metas(None);
+        assert!(send.len() >= SEND_ACCOUNTS_LEN);
+        assert!(send[0].is_signer);
+
+        let quote = mock_endpoint::accounts::Quote {
+            send_library_program: key,
+            send_library_config: key,
+            default_send_library_config: key,
+            send_library_info: key,
+            endpoint: key,
+            nonce: key,
+        }
+        .to_account_metas(None);
+        assert_eq!(quote.len(), QUOTE_ACCOUNTS_LEN);
+
+        let set_delegate = mock_endpoint::accounts::SetDelegate {
+            oapp: key,
+            oapp_registry: key,
+            event_authority: key,
+            program: key,
+        }
+        .to_account_metas(None);
+        assert_eq!(set_delegate.len(), SET_DELEGATE_ACCOUNTS_LEN);
+    }
+}
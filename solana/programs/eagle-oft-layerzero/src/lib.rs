@@ -1,5 +1,24 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Burn};
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_spl::associated_token::{
+    get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TokenMetadataInitialize,
+    TransferChecked,
+};
+
+pub mod compose_msg_codec;
+pub mod endpoint;
+pub mod msg_codec;
+pub mod options;
+pub mod token_extensions;
+
+use endpoint::{
+    EndpointClearParams, EndpointQuoteParams, EndpointRegisterOAppParams,
+    EndpointSendComposeParams, EndpointSendParams, EndpointSetDelegateParams,
+};
 
 declare_id!("EjpziSWGRcEiDHLXft5etbUtcJiZxEttkwz1tqiuzzWU");
 
@@ -11,207 +30,762 @@ pub const ETHEREUM_MAINNET_EID: u32 = 30101;
 pub const ETHEREUM_SEPOLIA_EID: u32 = 40161;
 
 /// Default to mainnet for compatibility
-pub const SOLANA_EID: u32 = SOLANA_MAINNET_EID;
 pub const ETHEREUM_EID: u32 = ETHEREUM_MAINNET_EID;
 
-/// Message types for OFT standard
-pub const MSG_TYPE_SEND: u8 = 0;
-pub const MSG_TYPE_SEND_AND_CALL: u8 = 1;
+/// Message types for enforced options, matching the EVM OFT's SEND / SEND_AND_CALL
+pub const MSG_TYPE_SEND: u8 = 1;
+pub const MSG_TYPE_SEND_AND_CALL: u8 = 2;
+
+/// Maximum length of the enforced options stored per peer and message type
+pub const MAX_ENFORCED_OPTIONS_LEN: usize = 128;
+
+/// Decimals of the EAGLE mint on Solana
+pub const LOCAL_DECIMALS: u8 = 9;
+
+/// Maximum number of role grants held by the role registry
+pub const MAX_ROLE_MEMBERS: usize = 16;
+
+/// Maximum number of executors on a peer's allowlist
+pub const MAX_PEER_EXECUTORS: usize = 4;
+
+/// Basis points denominator for bridge fees
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest bridge fee that can be set on a peer (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
 
-/// Decimals conversion: ETH (18) -> SOL (9)
-pub const SHARED_DECIMALS: u8 = 9;
+/// Longest timelock delay the admin can configure (30 days)
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
 
 #[program]
 pub mod eagle_oft_layerzero {
     use super::*;
 
     /// Initialize the OFT with LayerZero endpoint
+    /// Creates the EAGLE mint; tokens are burned on send and minted on receive
+    ///
+    /// `shared_decimals` must match the peers' `sharedDecimals()` and cannot
+    /// exceed the mint's `LOCAL_DECIMALS`. `local_eid` is the LayerZero EID
+    /// of the cluster being deployed to.
     pub fn initialize(
         ctx: Context<Initialize>,
         endpoint_program: Pubkey,
+        local_eid: u32,
         admin: Pubkey,
+        shared_decimals: u8,
     ) -> Result<()> {
         let config = &mut ctx.accounts.oft_config;
         
         config.admin = admin;
+        config.pending_admin = None;
         config.mint = ctx.accounts.mint.key();
+        config.token_program = ctx.accounts.token_program.key();
         config.endpoint_program = endpoint_program;
+        config.local_eid = local_eid;
+        config.mode = OftMode::MintBurn;
+        config.escrow = Pubkey::default();
+        config.fee_vault = Pubkey::default();
+        config.quarantine = Pubkey::default();
         config.paused = false;
+        config.non_blocking = false;
+        config.timelock_delay = 0;
         config.total_bridged_in = 0;
         config.total_bridged_out = 0;
+        config.set_decimals(LOCAL_DECIMALS, shared_decimals)?;
         config.bump = ctx.bumps.oft_config;
         
+        ctx.accounts.roles.bump = ctx.bumps.roles;
+        
         msg!("✅ EAGLE OFT LayerZero initialized");
         msg!("   Admin: {}", admin);
         msg!("   Mint: {}", config.mint);
         msg!("   Endpoint: {}", endpoint_program);
-        msg!("   Solana EID: {}", SOLANA_EID);
+        msg!("   Shared decimals: {}", shared_decimals);
+        msg!("   Local EID: {}", local_eid);
+        
+        Ok(())
+    }
+
+    /// Initialize the OFT as an adapter over an existing mint
+    /// Tokens are locked in a program-owned escrow on send and released on receive
+    ///
+    /// `shared_decimals` must match the peers' `sharedDecimals()` and cannot
+    /// exceed the mint's decimals. `local_eid` is the LayerZero EID of the
+    /// cluster being deployed to.
+    pub fn initialize_adapter(
+        ctx: Context<InitializeAdapter>,
+        endpoint_program: Pubkey,
+        local_eid: u32,
+        admin: Pubkey,
+        shared_decimals: u8,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        
+        config.admin = admin;
+        config.pending_admin = None;
+        config.mint = ctx.accounts.mint.key();
+        config.token_program = ctx.accounts.token_program.key();
+        config.endpoint_program = endpoint_program;
+        config.local_eid = local_eid;
+        config.mode = OftMode::LockUnlock;
+        config.escrow = ctx.accounts.escrow.key();
+        config.fee_vault = Pubkey::default();
+        config.quarantine = Pubkey::default();
+        config.paused = false;
+        config.non_blocking = false;
+        config.timelock_delay = 0;
+        config.total_bridged_in = 0;
+        config.total_bridged_out = 0;
+        config.set_decimals(ctx.accounts.mint.decimals, shared_decimals)?;
+        config.bump = ctx.bumps.oft_config;
+        
+        ctx.accounts.roles.bump = ctx.bumps.roles;
+        
+        msg!("✅ EAGLE OFT LayerZero adapter initialized");
+        msg!("   Admin: {}", admin);
+        msg!("   Mint: {}", config.mint);
+        msg!("   Escrow: {}", config.escrow);
+        msg!("   Endpoint: {}", endpoint_program);
+        msg!("   Shared decimals: {}", shared_decimals);
+        msg!("   Local EID: {}", local_eid);
+        
+        Ok(())
+    }
+
+    /// Initialize the OFT with a Token-2022 EAGLE mint
+    /// Same as `initialize`, but the mint carries its own name, symbol and
+    /// URI through the metadata-pointer and metadata extensions, with the
+    /// OFT config as update authority
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_token_2022(
+        ctx: Context<InitializeToken2022>,
+        endpoint_program: Pubkey,
+        local_eid: u32,
+        admin: Pubkey,
+        shared_decimals: u8,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        
+        config.admin = admin;
+        config.pending_admin = None;
+        config.mint = ctx.accounts.mint.key();
+        config.token_program = ctx.accounts.token_program.key();
+        config.endpoint_program = endpoint_program;
+        config.local_eid = local_eid;
+        config.mode = OftMode::MintBurn;
+        config.escrow = Pubkey::default();
+        config.fee_vault = Pubkey::default();
+        config.quarantine = Pubkey::default();
+        config.paused = false;
+        config.non_blocking = false;
+        config.timelock_delay = 0;
+        config.total_bridged_in = 0;
+        config.total_bridged_out = 0;
+        config.set_decimals(LOCAL_DECIMALS, shared_decimals)?;
+        config.bump = ctx.bumps.oft_config;
+        
+        ctx.accounts.roles.bump = ctx.bumps.roles;
+        
+        // The metadata extension grows the mint account, so fund the
+        // extra rent before writing it
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let space = mint_info.data_len()
+            + token_extensions::metadata_space(
+                config.mint,
+                config.key(),
+                &name,
+                &symbol,
+                &uri,
+            )?;
+        let top_up = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(mint_info.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: mint_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        
+        let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TokenMetadataInitialize {
+                    program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: mint_info.clone(),
+                    update_authority: config.to_account_info(),
+                    mint_authority: config.to_account_info(),
+                    mint: mint_info,
+                },
+                &[seeds],
+            ),
+            name.clone(),
+            symbol.clone(),
+            uri,
+        )?;
+        
+        msg!("✅ EAGLE OFT LayerZero initialized (Token-2022)");
+        msg!("   Admin: {}", admin);
+        msg!("   Mint: {} ({} / {})", config.mint, name, symbol);
+        msg!("   Endpoint: {}", endpoint_program);
+        msg!("   Shared decimals: {}", shared_decimals);
+        msg!("   Local EID: {}", local_eid);
+        
+        Ok(())
+    }
+
+    /// Register the OFT as an OApp at the LayerZero endpoint
+    ///
+    /// Required once before the endpoint accepts `send`, `clear` or
+    /// `set_delegate` from the OFT. No delegate is registered; grant
+    /// `Role::Delegate` to set one. Remaining accounts are the endpoint's
+    /// `register_oapp` accounts, paid for by the admin.
+    pub fn register_oapp<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterOApp<'info>>,
+    ) -> Result<()> {
+        let config = &ctx.accounts.oft_config;
+        let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+        endpoint::register_oapp(
+            &ctx.accounts.endpoint_program,
+            config.key(),
+            ctx.remaining_accounts,
+            seeds,
+            EndpointRegisterOAppParams {
+                delegate: Pubkey::default(),
+            },
+        )?;
+        
+        msg!("✅ Registered with LayerZero endpoint {}", config.endpoint_program);
         
         Ok(())
     }
 
     /// Set peer OFT on another chain (e.g., Ethereum)
+    /// Timelocked while a timelock delay is set
     pub fn set_peer(
         ctx: Context<SetPeer>,
         dst_eid: u32,
         peer: [u8; 32],
     ) -> Result<()> {
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::SetPeer { eid: dst_eid, address: peer },
+            &ctx.accounts.authority,
+        )?;
+        
         let peer_info = &mut ctx.accounts.peer_config;
         
         peer_info.eid = dst_eid;
         peer_info.address = peer;
         peer_info.enabled = true;
+        peer_info.ordered_execution = false;
+        peer_info.outbound_nonce = 0;
+        peer_info.enforced_options = EnforcedOptions::default();
+        peer_info.outbound_rate_limit = None;
+        peer_info.inbound_rate_limit = None;
+        peer_info.fee_bps = 0;
+        peer_info.executors = Vec::new();
         peer_info.bump = ctx.bumps.peer_config;
         
+        emit!(PeerSet {
+            eid: dst_eid,
+            address: peer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
         msg!("✅ Peer set for EID {}", dst_eid);
         msg!("   Address: {:?}", peer);
         
         Ok(())
     }
 
+    /// Point an existing peer at a new OFT address (e.g. a redeployed EVM OFT)
+    ///
+    /// The endpoint tracks outbound nonces per receiver, so the peer's
+    /// outbound nonce restarts. Other peer settings are kept. Timelocked
+    /// while a timelock delay is set.
+    pub fn set_peer_address(
+        ctx: Context<SetPeerAddress>,
+        dst_eid: u32,
+        peer: [u8; 32],
+    ) -> Result<()> {
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::SetPeerAddress { eid: dst_eid, address: peer },
+            &ctx.accounts.authority,
+        )?;
+        
+        let peer_info = &mut ctx.accounts.peer_config;
+        let old_address = peer_info.address;
+        
+        peer_info.address = peer;
+        peer_info.outbound_nonce = 0;
+        
+        emit!(PeerAddressUpdated {
+            eid: dst_eid,
+            old_address,
+            new_address: peer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("🔧 Peer address updated for EID {}", dst_eid);
+        msg!("   Old: {:?}", old_address);
+        msg!("   New: {:?}", peer);
+        
+        Ok(())
+    }
+
+    /// Remove a peer and return its rent to the caller
+    /// Timelocked while a timelock delay is set
+    pub fn close_peer(
+        ctx: Context<ClosePeer>,
+        dst_eid: u32,
+    ) -> Result<()> {
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::ClosePeer { eid: dst_eid },
+            &ctx.accounts.authority,
+        )?;
+        
+        emit!(PeerClosed {
+            eid: dst_eid,
+            address: ctx.accounts.peer_config.address,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("🗑️ Peer closed for EID {}", dst_eid);
+        
+        Ok(())
+    }
+
     /// Send tokens to another chain via LayerZero
     /// Users call this to bridge tokens OUT from Solana
-    pub fn send(
-        ctx: Context<Send>,
+    ///
+    /// Remaining accounts are forwarded to the endpoint's `send` instruction:
+    /// the endpoint accounts (starting with `oft_config` as the OApp signer)
+    /// followed by the send library accounts. The sender pays `fee.native_fee`.
+    /// For transfer-hook mints, the hook's accounts go before those, up to
+    /// the first `oft_config`.
+    ///
+    /// `dst_eid` selects the peer account and must match `send_param.dst_eid`.
+    pub fn send<'info>(
+        ctx: Context<'_, '_, '_, 'info, Send<'info>>,
+        dst_eid: u32,
         send_param: SendParam,
+        fee: MessagingFee,
     ) -> Result<SendReceipt> {
-        let config = &mut ctx.accounts.oft_config;
+        let config = &ctx.accounts.oft_config;
         let peer = &ctx.accounts.peer_config;
         
         require!(!config.paused, OftError::Paused);
+        require!(peer.eid == dst_eid, OftError::InvalidPeer);
+        require!(send_param.dst_eid == dst_eid, OftError::InvalidPeer);
         require!(peer.enabled, OftError::PeerDisabled);
         require!(
-            send_param.amount_ld >= send_param.min_amount_ld,
-            OftError::SlippageExceeded
+            !is_blocked(&ctx.accounts.sender_blocked),
+            OftError::AddressBlocked
+        );
+        require!(
+            !is_blocked(&ctx.accounts.recipient_blocked),
+            OftError::AddressBlocked
         );
         
-        // Burn tokens from sender
-        token::burn(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Burn {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    from: ctx.accounts.from.to_account_info(),
-                    authority: ctx.accounts.sender.to_account_info(),
-                },
-            ),
-            send_param.amount_ld,
-        )?;
-
-        // Update stats
-        config.total_bridged_out = config.total_bridged_out
-            .checked_add(send_param.amount_ld)
-            .ok_or(OftError::Overflow)?;
-
-        // Encode OFT message
-        let message = encode_oft_message(
-            MSG_TYPE_SEND,
-            send_param.to,
-            send_param.amount_ld,
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let (hook_accounts, endpoint_accounts) = ctx.remaining_accounts.split_at(
+            ctx.remaining_accounts
+                .iter()
+                .position(|account| account.key() == config.key())
+                .unwrap_or(0),
         );
         
-        // Generate GUID
-        let guid = generate_guid(
+        let debit = debit_view(config, peer, &mint_info, send_param.amount_ld)?;
+        let amount_sent_ld = debit.amount_sent_ld;
+        debit.check_slippage(send_param.min_amount_ld)?;
+        
+        // Collect the bridge fee in EAGLE
+        if debit.bridge_fee_ld > 0 {
+            let fee_vault = ctx.accounts.fee_vault.as_ref().ok_or(OftError::InvalidFeeVault)?;
+            
+            token_extensions::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.from.to_account_info(),
+                        mint: mint_info.clone(),
+                        to: fee_vault.to_account_info(),
+                        authority: ctx.accounts.sender.to_account_info(),
+                    },
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                debit.bridge_fee_ld,
+                ctx.accounts.mint.decimals,
+            )?;
+        }
+        
+        // Take the tokens out of circulation on Solana
+        match config.mode {
+            OftMode::MintBurn => {
+                let supply_before = ctx.accounts.mint.supply;
+                
+                token_interface::burn(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Burn {
+                            mint: ctx.accounts.mint.to_account_info(),
+                            from: ctx.accounts.from.to_account_info(),
+                            authority: ctx.accounts.sender.to_account_info(),
+                        },
+                    ),
+                    amount_sent_ld,
+                )?;
+                
+                ctx.accounts.mint.reload()?;
+                require!(
+                    supply_before.checked_sub(ctx.accounts.mint.supply) == Some(amount_sent_ld),
+                    OftError::SupplyInvariantViolated
+                );
+            }
+            OftMode::LockUnlock => {
+                let escrow = ctx.accounts.escrow.as_mut().ok_or(OftError::InvalidEscrow)?;
+                let escrowed_before = escrow.amount;
+                let amount_in_ld = token_extensions::gross_amount(
+                    &ctx.accounts.mint.to_account_info(),
+                    amount_sent_ld,
+                )?;
+                
+                token_extensions::transfer_checked(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: ctx.accounts.from.to_account_info(),
+                            mint: ctx.accounts.mint.to_account_info(),
+                            to: escrow.to_account_info(),
+                            authority: ctx.accounts.sender.to_account_info(),
+                        },
+                    )
+                    .with_remaining_accounts(hook_accounts.to_vec()),
+                    amount_in_ld,
+                    ctx.accounts.mint.decimals,
+                )?;
+                
+                escrow.reload()?;
+                require!(
+                    escrow.amount.checked_sub(escrowed_before) == Some(amount_sent_ld),
+                    OftError::SupplyInvariantViolated
+                );
+            }
+        }
+
+        // Encode OFT message
+        let message = build_oft_message(
+            &send_param,
+            config.to_sd(amount_sent_ld),
             ctx.accounts.sender.key(),
-            send_param.dst_eid,
-            send_param.amount_ld,
-            Clock::get()?.unix_timestamp as u64,
         );
         
-        // In full LayerZero implementation, we would CPI to endpoint here:
-        // lz_endpoint::cpi::send(
-        //     CpiContext::new(
-        //         ctx.accounts.endpoint_program.to_account_info(),
-        //         lz_endpoint::cpi::accounts::Send { ... },
-        //     ),
-        //     SendParams { dst_eid, to, amount, options, ... }
-        // )?;
+        let options = peer.enforced_options.combine(&send_param)?;
+        
+        // Hand the message to the LayerZero endpoint, signing as the OApp
+        let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+        let receipt = endpoint::send(
+            &ctx.accounts.endpoint_program,
+            config.key(),
+            endpoint_accounts,
+            seeds,
+            EndpointSendParams {
+                dst_eid: send_param.dst_eid,
+                receiver: peer.address,
+                message,
+                options,
+                native_fee: fee.native_fee,
+                lz_token_fee: fee.lz_token_fee,
+            },
+        )?;
+
+        // Track outbound volume and the per-peer nonce assigned by the endpoint
+        let peer = &mut ctx.accounts.peer_config;
+        peer.consume_rate_limit(
+            RateLimitDirection::Outbound,
+            amount_sent_ld,
+            Clock::get()?.unix_timestamp,
+        )?;
+        require!(receipt.nonce > peer.outbound_nonce, OftError::InvalidNonce);
+        peer.outbound_nonce = receipt.nonce;
+
+        // Update stats
+        let config = &mut ctx.accounts.oft_config;
+        config.total_bridged_out = config.total_bridged_out
+            .checked_add(amount_sent_ld)
+            .ok_or(OftError::Overflow)?;
         
-        // For now, emit event for DVNs to pick up
         emit!(SendEvent {
-            guid,
-            src_eid: SOLANA_EID,
+            guid: receipt.guid,
+            src_eid: config.local_eid,
             dst_eid: send_param.dst_eid,
+            nonce: receipt.nonce,
             to: send_param.to,
-            amount_ld: send_param.amount_ld,
+            amount_ld: amount_sent_ld,
+            fee_ld: debit.bridge_fee_ld,
             sender: ctx.accounts.sender.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
 
-        msg!("🚀 Sent {} tokens to EID {} (GUID: {:?})", 
-            send_param.amount_ld, send_param.dst_eid, guid);
+        msg!("🚀 Sent {} tokens to EID {} (GUID: {:?}, nonce: {})", 
+            amount_sent_ld, send_param.dst_eid, receipt.guid, receipt.nonce);
         
         Ok(SendReceipt {
-            guid,
-            nonce: config.total_bridged_out, // Use as nonce
-            fee: MessagingFee { native_fee: 0, lz_token_fee: 0 },
+            guid: receipt.guid,
+            nonce: receipt.nonce,
+            fee: receipt.fee,
         })
     }
 
     /// Receive tokens from another chain via LayerZero
-    /// Called by LayerZero endpoint when message arrives
-    pub fn lz_receive(
-        ctx: Context<LzReceive>,
+    /// Called by the LayerZero executor once the message is verified
+    ///
+    /// The first `endpoint::CLEAR_ACCOUNTS_LEN` remaining accounts are the
+    /// endpoint's `clear` accounts; clearing the payload proves the message
+    /// was verified and burns it at the endpoint. Send-and-call messages
+    /// additionally need the endpoint's `send_compose` accounts after those,
+    /// so the compose payload can be queued for the recipient. The accounts
+    /// of the mint's transfer hook, if any, come last. Each message is then
+    /// executed at most once: its GUID is marked as consumed and, for peers
+    /// with ordered execution, nonces must arrive in sequence.
+    ///
+    /// In non-blocking mode a verified message that cannot be delivered
+    /// (paused OFT, disabled peer, exhausted rate limit, short escrow) is
+    /// stored in its `FailedMessage` PDA instead of reverting, to be retried,
+    /// cleared or refunded later.
+    ///
+    /// If the peer has an executor allowlist, the executor must be on it and
+    /// sign as payer. Native drops are left to the executor, which pays them
+    /// outside this instruction.
+    ///
+    /// Tokens for a blocked recipient are credited to the quarantine account
+    /// instead, without compose.
+    pub fn lz_receive<'info>(
+        ctx: Context<'_, '_, '_, 'info, LzReceive<'info>>,
         origin: Origin,
         guid: [u8; 32],
         message: Vec<u8>,
         executor: Pubkey,
-        extra_data: Vec<u8>,
+        _extra_data: Vec<u8>,
     ) -> Result<()> {
-        let config = &mut ctx.accounts.oft_config;
+        let config = &ctx.accounts.oft_config;
         let peer = &ctx.accounts.peer_config;
         
-        require!(!config.paused, OftError::Paused);
-        require!(peer.enabled, OftError::PeerDisabled);
-        
         // Verify message comes from our peer
         require!(peer.eid == origin.src_eid, OftError::InvalidPeer);
         require!(peer.address == origin.sender, OftError::InvalidPeer);
         
-        // Decode OFT message
-        let (msg_type, to_address, amount_ld) = decode_oft_message(&message)?;
-        require!(msg_type == MSG_TYPE_SEND, OftError::InvalidMessageType);
-        
-        // Convert bytes32 to Solana Pubkey
-        let recipient = Pubkey::new_from_array(to_address);
+        if !peer.executors.is_empty() {
+            require!(peer.executors.contains(&executor), OftError::UnauthorizedExecutor);
+            require_keys_eq!(
+                executor,
+                ctx.accounts.payer.key(),
+                OftError::UnauthorizedExecutor
+            );
+        }
         
-        // Verify recipient token account matches
+        // The GUID keys the replay marker, so it must be the one LayerZero
+        // assigned to this packet
         require!(
-            ctx.accounts.to.owner == recipient,
-            OftError::InvalidRecipient
+            guid == generate_guid(
+                origin.nonce,
+                origin.src_eid,
+                origin.sender,
+                config.local_eid,
+                config.key().to_bytes(),
+            ),
+            OftError::InvalidGuid
         );
         
-        // Update stats
-        config.total_bridged_in = config.total_bridged_in
-            .checked_add(amount_ld)
-            .ok_or(OftError::Overflow)?;
+        // Replay and ordering checks
+        let received = &mut ctx.accounts.received_message;
+        require!(!received.executed, OftError::MessageAlreadyExecuted);
+        received.executed = true;
+        received.bump = ctx.bumps.received_message;
+        
+        let inbound_nonce = &mut ctx.accounts.inbound_nonce;
+        if peer.ordered_execution {
+            require!(
+                origin.nonce == inbound_nonce.last_nonce + 1,
+                OftError::InvalidNonce
+            );
+        }
+        inbound_nonce.src_eid = origin.src_eid;
+        inbound_nonce.sender = origin.sender;
+        inbound_nonce.last_nonce = inbound_nonce.last_nonce.max(origin.nonce);
+        inbound_nonce.bump = ctx.bumps.inbound_nonce;
         
-        // Mint tokens to recipient
-        let seeds = &[
+        // Burn the verified payload at the endpoint
+        let seeds: &[&[u8]] = &[
             b"oft_config",
             &[config.bump],
         ];
-        let signer = &[&seeds[..]];
+        endpoint::clear(
+            &ctx.accounts.endpoint_program,
+            config.key(),
+            ctx.remaining_accounts,
+            seeds,
+            EndpointClearParams {
+                receiver: config.key(),
+                src_eid: origin.src_eid,
+                sender: origin.sender,
+                nonce: origin.nonce,
+                guid,
+                message: message.clone(),
+            },
+        )?;
         
-        token::mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.to.to_account_info(),
-                    authority: ctx.accounts.oft_config.to_account_info(),
+        // Decode OFT message
+        let oft_message = msg_codec::decode(&message)?;
+        let amount_ld = config.to_ld(oft_message.amount_sd)?;
+        let now = Clock::get()?.unix_timestamp;
+        
+        // Convert bytes32 to Solana Pubkey
+        let recipient = Pubkey::new_from_array(oft_message.send_to);
+        
+        // Tokens go to the recipient's associated token account, created
+        // above at the executor's expense if it did not exist yet
+        require_keys_eq!(
+            ctx.accounts.to_address.key(),
+            recipient,
+            OftError::InvalidRecipient
+        );
+        
+        let blocked = is_blocked(&ctx.accounts.recipient_blocked);
+        
+        if let Some(reason) = delivery_failure(
+            config,
+            peer,
+            ctx.accounts.escrow.as_ref(),
+            amount_ld,
+            now,
+        )? {
+            if !config.non_blocking {
+                return Err(reason.error().into());
+            }
+            
+            store_failed_message(
+                &ctx.accounts.failed_message,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                ctx.bumps.failed_message,
+                &FailedMessage {
+                    guid,
+                    src_eid: origin.src_eid,
+                    sender: origin.sender,
+                    nonce: origin.nonce,
+                    recipient,
+                    amount_ld,
+                    compose_msg: oft_message.compose_msg.unwrap_or_default(),
+                    reason,
+                    payer: ctx.accounts.payer.key(),
+                    failed_at: now,
+                    bump: ctx.bumps.failed_message,
                 },
-                signer,
-            ),
+            )?;
+            
+            emit!(MessageFailed {
+                guid,
+                src_eid: origin.src_eid,
+                nonce: origin.nonce,
+                reason,
+                timestamp: now,
+            });
+            
+            msg!("⚠️ Stored undeliverable message from EID {} ({:?}, GUID: {:?})",
+                origin.src_eid, reason, guid);
+            
+            return Ok(());
+        }
+        
+        ctx.accounts.peer_config.consume_rate_limit(
+            RateLimitDirection::Inbound,
+            amount_ld,
+            now,
+        )?;
+        
+        // Mint or release tokens to recipient, or to quarantine if blocked
+        let to = if blocked {
+            ctx.accounts.quarantine.as_ref().ok_or(OftError::InvalidQuarantine)?
+        } else {
+            &ctx.accounts.to
+        };
+        let endpoint_accounts_len = match oft_message.compose_msg {
+            Some(_) => endpoint::CLEAR_ACCOUNTS_LEN + endpoint::SEND_COMPOSE_ACCOUNTS_LEN,
+            None => endpoint::CLEAR_ACCOUNTS_LEN,
+        };
+        let amount_received_ld = credit(
+            &ctx.accounts.oft_config,
+            &mut ctx.accounts.mint,
+            ctx.accounts.escrow.as_mut(),
+            to,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts.get(endpoint_accounts_len..).unwrap_or_default(),
             amount_ld,
         )?;
+        
+        if blocked {
+            emit!(TransferQuarantined {
+                guid,
+                src_eid: origin.src_eid,
+                recipient,
+                amount_ld,
+                timestamp: now,
+            });
+            
+            msg!("🚫 Recipient {} is blocked; {} tokens quarantined", recipient, amount_ld);
+        }
+
+        // Hand the compose payload to the endpoint's compose queue
+        if let Some(compose_msg) = oft_message.compose_msg.as_ref().filter(|_| !blocked) {
+            endpoint::send_compose(
+                &ctx.accounts.endpoint_program,
+                config.key(),
+                &ctx.remaining_accounts[endpoint::CLEAR_ACCOUNTS_LEN..],
+                seeds,
+                EndpointSendComposeParams {
+                    to: recipient,
+                    guid,
+                    index: 0,
+                    message: compose_msg_codec::encode(
+                        origin.nonce,
+                        origin.src_eid,
+                        amount_received_ld,
+                        compose_msg,
+                    ),
+                },
+            )?;
+        }
+
+        // Update stats
+        let config = &mut ctx.accounts.oft_config;
+        config.total_bridged_in = config.total_bridged_in
+            .checked_add(amount_ld)
+            .ok_or(OftError::Overflow)?;
 
         emit!(ReceiveEvent {
             guid,
             src_eid: origin.src_eid,
-            dst_eid: SOLANA_EID,
+            dst_eid: config.local_eid,
             to: recipient,
             amount_ld,
+            amount_received_ld,
             nonce: origin.nonce,
             timestamp: Clock::get()?.unix_timestamp,
         });
@@ -222,222 +796,2112 @@ pub mod eagle_oft_layerzero {
         Ok(())
     }
 
-    /// Quote the fee for sending tokens cross-chain
-    pub fn quote_send(
-        _ctx: Context<QuoteSend>,
-        send_param: SendParam,
-        _pay_in_lz_token: bool,
-    ) -> Result<MessagingFee> {
-        // In full implementation, this would CPI to LayerZero endpoint
-        // to get real-time fee quote based on:
-        // - Destination chain
-        // - Message size
-        // - DVN configuration
-        // - Gas prices
-        
-        // Estimated fees for Solana -> Ethereum:
-        // - DVN verification: ~0.0005 SOL per DVN (need 2+ DVNs)
-        // - Executor gas on Ethereum: ~0.001 SOL equivalent
-        // Total: ~0.002-0.005 SOL
-        
-        let base_fee = 2_000_000; // 0.002 SOL in lamports
-        let per_byte_fee = 100; // Small fee per byte
-        
-        let message_size = 1 + 32 + 8; // msgType + to + amount
-        let native_fee = base_fee + (message_size * per_byte_fee);
-        
-        Ok(MessagingFee {
-            native_fee,
-            lz_token_fee: 0, // Not using LZ token payment
-        })
+    /// List the accounts `lz_receive` needs for a message, in order
+    ///
+    /// Lets the LayerZero executor build the `lz_receive` transaction on its
+    /// own: the `LzReceive` accounts followed by the endpoint's `clear`
+    /// accounts and, for send-and-call messages, its `send_compose` accounts.
+    /// `Pubkey::default()` marks the payer, which the executor replaces with
+    /// itself.
+    ///
+    /// In lock/unlock mode, transfer-hook mints also need the hook's accounts
+    /// for the release from the escrow; the remaining account must then be
+    /// the hook's extra-account-metas account.
+    pub fn lz_receive_types(
+        ctx: Context<LzReceiveTypes>,
+        origin: Origin,
+        guid: [u8; 32],
+        message: Vec<u8>,
+    ) -> Result<Vec<LzAccount>> {
+        let config = &ctx.accounts.oft_config;
+        let mut accounts = lz_receive_accounts(config, config.key(), &origin, &guid, &message)?;
+        
+        if config.mode == OftMode::LockUnlock {
+            let oft_message = msg_codec::decode(&message)?;
+            let recipient = Pubkey::new_from_array(oft_message.send_to);
+            accounts.extend(
+                token_extensions::transfer_hook_accounts(
+                    &ctx.accounts.mint.to_account_info(),
+                    ctx.remaining_accounts.first(),
+                    config.escrow,
+                    get_associated_token_address_with_program_id(
+                        &recipient,
+                        &config.mint,
+                        &config.token_program,
+                    ),
+                    config.key(),
+                    config.to_ld(oft_message.amount_sd)?,
+                )?
+                .into_iter()
+                .map(LzAccount::from),
+            );
+        }
+        
+        Ok(accounts)
     }
 
-    /// Emergency pause/unpause
-    pub fn set_paused(
-        ctx: Context<SetPaused>,
-        paused: bool,
+    /// Deliver a stored failed message once the cause has been resolved
+    ///
+    /// Anyone may retry. Send-and-call messages need the endpoint's
+    /// `send_compose` accounts as remaining accounts, followed by the
+    /// accounts of the mint's transfer hook, if any. The stored message's
+    /// rent goes back to whoever paid for it. Tokens for a recipient blocked
+    /// in the meantime go to the quarantine account.
+    pub fn retry_message<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetryMessage<'info>>,
     ) -> Result<()> {
+        let config = &ctx.accounts.oft_config;
+        let failed = &ctx.accounts.failed_message;
+        let now = Clock::get()?.unix_timestamp;
+        
+        if let Some(reason) = delivery_failure(
+            config,
+            &ctx.accounts.peer_config,
+            ctx.accounts.escrow.as_ref(),
+            failed.amount_ld,
+            now,
+        )? {
+            return Err(reason.error().into());
+        }
+        
+        let (guid, amount_ld) = (failed.guid, failed.amount_ld);
+        ctx.accounts.peer_config.consume_rate_limit(
+            RateLimitDirection::Inbound,
+            amount_ld,
+            now,
+        )?;
+        
+        let blocked = is_blocked(&ctx.accounts.recipient_blocked);
+        let to = if blocked {
+            ctx.accounts.quarantine.as_ref().ok_or(OftError::InvalidQuarantine)?
+        } else {
+            &ctx.accounts.to
+        };
+        let endpoint_accounts_len = if failed.compose_msg.is_empty() {
+            0
+        } else {
+            endpoint::SEND_COMPOSE_ACCOUNTS_LEN
+        };
+        let amount_received_ld = credit(
+            &ctx.accounts.oft_config,
+            &mut ctx.accounts.mint,
+            ctx.accounts.escrow.as_mut(),
+            to,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts.get(endpoint_accounts_len..).unwrap_or_default(),
+            amount_ld,
+        )?;
+        
+        let config = &ctx.accounts.oft_config;
+        let failed = &ctx.accounts.failed_message;
+        if blocked {
+            emit!(TransferQuarantined {
+                guid,
+                src_eid: failed.src_eid,
+                recipient: failed.recipient,
+                amount_ld,
+                timestamp: now,
+            });
+            
+            msg!("🚫 Recipient {} is blocked; {} tokens quarantined", failed.recipient, amount_ld);
+        } else if !failed.compose_msg.is_empty() {
+            let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+            endpoint::send_compose(
+                &ctx.accounts.endpoint_program,
+                config.key(),
+                ctx.remaining_accounts,
+                seeds,
+                EndpointSendComposeParams {
+                    to: failed.recipient,
+                    guid,
+                    index: 0,
+                    message: compose_msg_codec::encode(
+                        failed.nonce,
+                        failed.src_eid,
+                        amount_received_ld,
+                        &failed.compose_msg,
+                    ),
+                },
+            )?;
+        }
+        
+        let (src_eid, nonce, recipient) = (failed.src_eid, failed.nonce, failed.recipient);
         let config = &mut ctx.accounts.oft_config;
-        config.paused = paused;
+        config.total_bridged_in = config.total_bridged_in
+            .checked_add(amount_ld)
+            .ok_or(OftError::Overflow)?;
         
-        msg!("🔒 OFT paused status: {}", paused);
+        emit!(ReceiveEvent {
+            guid,
+            src_eid,
+            dst_eid: config.local_eid,
+            to: recipient,
+            amount_ld,
+            amount_received_ld,
+            nonce,
+            timestamp: now,
+        });
+        
+        msg!("🔁 Retried message from EID {}: {} tokens for {} (GUID: {:?})",
+            src_eid, amount_ld, recipient, guid);
         
         Ok(())
     }
 
-    /// Enable/disable a peer
-    pub fn set_peer_enabled(
-        ctx: Context<SetPeerEnabled>,
-        dst_eid: u32,
-        enabled: bool,
-    ) -> Result<()> {
-        let peer = &mut ctx.accounts.peer_config;
-        peer.enabled = enabled;
+    /// Drop a stored failed message without delivering or refunding it
+    pub fn clear_message(ctx: Context<ClearMessage>) -> Result<()> {
+        let failed = &ctx.accounts.failed_message;
         
-        msg!("🔧 Peer EID {} enabled: {}", dst_eid, enabled);
+        emit!(MessageCleared {
+            guid: failed.guid,
+            src_eid: failed.src_eid,
+            amount_ld: failed.amount_ld,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("🗑️ Cleared failed message (GUID: {:?})", failed.guid);
         
         Ok(())
     }
 
-    /// Transfer admin role
-    pub fn transfer_admin(
-        ctx: Context<TransferAdmin>,
-        new_admin: Pubkey,
-    ) -> Result<()> {
-        let config = &mut ctx.accounts.oft_config;
-        let old_admin = config.admin;
-        config.admin = new_admin;
+    /// Send the tokens of a stored failed message back to `refund_to` on the
+    /// source chain
+    ///
+    /// Nothing was credited on Solana, so no tokens move here; the source
+    /// peer credits `refund_to` like any inbound transfer. Remaining
+    /// accounts are the endpoint's `send` accounts as for `send`, and the
+    /// admin pays `fee.native_fee`.
+    ///
+    /// Composed messages carry their source sender and must be refunded to
+    /// it. Plain OFT messages are refunded to their recipient; any other
+    /// `refund_to` is timelocked while a timelock delay is set. Refunds
+    /// count against the outbound rate limit.
+    pub fn refund<'info>(
+        ctx: Context<'_, '_, '_, 'info, Refund<'info>>,
+        refund_to: [u8; 32],
+        extra_options: Vec<u8>,
+        fee: MessagingFee,
+    ) -> Result<SendReceipt> {
+        let config = &ctx.accounts.oft_config;
+        let peer = &ctx.accounts.peer_config;
+        let failed = &ctx.accounts.failed_message;
+        
+        require!(!config.paused, OftError::Paused);
+        require!(peer.enabled, OftError::PeerDisabled);
+        failed.check_refund_to(refund_to)?;
+        if failed.refund_needs_timelock(refund_to) {
+            consume_timelock(
+                config,
+                ctx.accounts.timelock_operation.as_ref(),
+                TimelockAction::Refund { guid: failed.guid, refund_to },
+                &ctx.accounts.admin,
+            )?;
+        }
         
-        msg!("👑 Admin transferred from {} to {}", old_admin, new_admin);
+        let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+        let receipt = endpoint::send(
+            &ctx.accounts.endpoint_program,
+            config.key(),
+            ctx.remaining_accounts,
+            seeds,
+            EndpointSendParams {
+                dst_eid: failed.src_eid,
+                receiver: peer.address,
+                message: msg_codec::encode(
+                    refund_to,
+                    config.to_sd(failed.amount_ld),
+                    Pubkey::default().to_bytes(),
+                    &[],
+                ),
+                options: options::combine(&peer.enforced_options.send, &extra_options)?,
+                native_fee: fee.native_fee,
+                lz_token_fee: fee.lz_token_fee,
+            },
+        )?;
         
-        Ok(())
+        let (guid, src_eid, amount_ld) = (failed.guid, failed.src_eid, failed.amount_ld);
+        let peer = &mut ctx.accounts.peer_config;
+        peer.consume_rate_limit(
+            RateLimitDirection::Outbound,
+            amount_ld,
+            Clock::get()?.unix_timestamp,
+        )?;
+        require!(receipt.nonce > peer.outbound_nonce, OftError::InvalidNonce);
+        peer.outbound_nonce = receipt.nonce;
+        
+        emit!(MessageRefunded {
+            guid,
+            refund_guid: receipt.guid,
+            dst_eid: src_eid,
+            to: refund_to,
+            amount_ld,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("↩️ Refunded {} tokens to EID {} (GUID: {:?})", amount_ld, src_eid, guid);
+        
+        Ok(SendReceipt {
+            guid: receipt.guid,
+            nonce: receipt.nonce,
+            fee: receipt.fee,
+        })
     }
-}
-
-// ============================================================================
-// Account Structures
-// ============================================================================
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + OftConfig::INIT_SPACE,
-        seeds = [b"oft_config"],
-        bump
+    /// Quote the fee for sending tokens cross-chain
+    ///
+    /// Builds the same message and options `send` would hand to the endpoint
+    /// and asks the endpoint's `quote` instruction for the fee. Remaining
+    /// accounts are the endpoint's quote accounts followed by the send
+    /// library accounts.
+    pub fn quote_send<'info>(
+        ctx: Context<'_, '_, '_, 'info, QuoteSend<'info>>,
+        send_param: SendParam,
+        pay_in_lz_token: bool,
+    ) -> Result<MessagingFee> {
+        let config = &ctx.accounts.oft_config;
+        let peer = &ctx.accounts.peer_config;
+        
+        require!(peer.enabled, OftError::PeerDisabled);
+        
+        let debit = debit_view(
+            config,
+            peer,
+            &ctx.accounts.mint.to_account_info(),
+            send_param.amount_ld,
+        )?;
+        
+        endpoint::quote(
+            &ctx.accounts.endpoint_program,
+            ctx.remaining_accounts,
+            EndpointQuoteParams {
+                sender: config.key(),
+                dst_eid: send_param.dst_eid,
+                receiver: peer.address,
+                // composeFrom only affects the message size here
+                message: build_oft_message(
+                    &send_param,
+                    config.to_sd(debit.amount_sent_ld),
+                    Pubkey::default(),
+                ),
+                options: peer.enforced_options.combine(&send_param)?,
+                pay_in_lz_token,
+            },
+        )
+    }
+
+    /// Preview a transfer like the EVM `quoteOFT`: sendable limits, itemized
+    /// fees and the amounts debited and received for `send_param`
+    ///
+    /// The maximum applies to the amount bridged after fees, for `sender`
+    /// sending from `from`; without `from` it is bounded by the outbound
+    /// rate limit only. It is zero while `send` would be refused.
+    pub fn quote_oft(
+        ctx: Context<QuoteOft>,
+        send_param: SendParam,
+    ) -> Result<OftQuote> {
+        let config = &ctx.accounts.oft_config;
+        let peer = &ctx.accounts.peer_config;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        
+        let debit = debit_view(config, peer, &mint_info, send_param.amount_ld)?;
+        let blocked = is_blocked(&ctx.accounts.sender_blocked)
+            || is_blocked(&ctx.accounts.recipient_blocked);
+        
+        Ok(OftQuote {
+            limit: send_limit(
+                config,
+                peer,
+                &mint_info,
+                ctx.accounts.from.as_ref().map(|from| from.amount),
+                blocked,
+                Clock::get()?.unix_timestamp,
+            )?,
+            fee_details: debit.fee_details(),
+            receipt: OftReceipt {
+                amount_sent_ld: debit.amount_debited_ld,
+                amount_received_ld: debit.amount_sent_ld,
+            },
+        })
+    }
+
+    /// Create the token account bridge fees are collected in
+    pub fn initialize_fee_vault(ctx: Context<InitializeFeeVault>) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        config.fee_vault = ctx.accounts.fee_vault.key();
+        
+        msg!("💰 Fee vault: {}", config.fee_vault);
+        
+        Ok(())
+    }
+
+    /// Set the bridge fee charged on sends to a peer, in basis points
+    /// Timelocked while a timelock delay is set
+    pub fn set_peer_fee(
+        ctx: Context<SetPeerFee>,
+        dst_eid: u32,
+        fee_bps: u16,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, OftError::InvalidFee);
+        require!(
+            fee_bps == 0 || ctx.accounts.oft_config.fee_vault != Pubkey::default(),
+            OftError::InvalidFeeVault
+        );
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::SetPeerFee { eid: dst_eid, fee_bps },
+            &ctx.accounts.authority,
+        )?;
+        
+        ctx.accounts.peer_config.fee_bps = fee_bps;
+        
+        msg!("💰 Bridge fee for EID {}: {} bps", dst_eid, fee_bps);
+        
+        Ok(())
+    }
+
+    /// Withdraw collected bridge fees to any token account of the mint
+    ///
+    /// Remaining accounts are the accounts of the mint's transfer hook, if any.
+    pub fn withdraw_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.oft_config;
+        let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+        
+        token_extensions::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.fee_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.to.to_account_info(),
+                    authority: config.to_account_info(),
+                },
+                &[seeds],
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        
+        emit!(FeesWithdrawn {
+            to: ctx.accounts.to.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("💰 Withdrew {} in fees to {}", amount, ctx.accounts.to.key());
+        
+        Ok(())
+    }
+
+    /// Create the token account that holds inbound transfers to blocked
+    /// recipients
+    pub fn initialize_quarantine(ctx: Context<InitializeQuarantine>) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        config.quarantine = ctx.accounts.quarantine.key();
+        
+        msg!("🚫 Quarantine: {}", config.quarantine);
+        
+        Ok(())
+    }
+
+    /// Block a Solana pubkey or bytes32 address from bridging
+    ///
+    /// Blocked senders cannot `send`, sends to blocked addresses are
+    /// rejected, and inbound transfers to blocked recipients are quarantined.
+    pub fn block_address(
+        ctx: Context<BlockAddress>,
+        address: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.oft_config.quarantine != Pubkey::default(),
+            OftError::InvalidQuarantine
+        );
+        
+        let now = Clock::get()?.unix_timestamp;
+        let blocked = &mut ctx.accounts.blocked_address;
+        blocked.address = address;
+        blocked.blocked_at = now;
+        blocked.bump = ctx.bumps.blocked_address;
+        
+        emit!(AddressBlocked {
+            address,
+            timestamp: now,
+        });
+        
+        msg!("🚫 Blocked {:?}", address);
+        
+        Ok(())
+    }
+
+    /// Remove an address from the blocklist
+    pub fn unblock_address(
+        _ctx: Context<UnblockAddress>,
+        address: [u8; 32],
+    ) -> Result<()> {
+        emit!(AddressUnblocked {
+            address,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("✅ Unblocked {:?}", address);
+        
+        Ok(())
+    }
+
+    /// Move quarantined tokens to any token account of the mint
+    ///
+    /// Remaining accounts are the accounts of the mint's transfer hook, if any.
+    pub fn release_quarantined<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReleaseQuarantined<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let config = &ctx.accounts.oft_config;
+        let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+        
+        token_extensions::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.quarantine.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.to.to_account_info(),
+                    authority: config.to_account_info(),
+                },
+                &[seeds],
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        
+        emit!(QuarantineReleased {
+            to: ctx.accounts.to.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("🔓 Released {} quarantined tokens to {}", amount, ctx.accounts.to.key());
+        
+        Ok(())
+    }
+
+    /// Emergency pause/unpause
+    /// Pausers can only pause; unpausing is left to the admin
+    pub fn set_paused(
+        ctx: Context<SetPaused>,
+        paused: bool,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        if !paused {
+            require_keys_eq!(
+                ctx.accounts.authority.key(),
+                config.admin,
+                OftError::Unauthorized
+            );
+        }
+        config.paused = paused;
+        
+        msg!("🔒 OFT paused status: {}", paused);
+        
+        Ok(())
+    }
+
+    /// Store undeliverable inbound messages instead of rejecting them
+    pub fn set_non_blocking(
+        ctx: Context<SetNonBlocking>,
+        non_blocking: bool,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        config.non_blocking = non_blocking;
+        
+        msg!("🔧 Non-blocking receive: {}", non_blocking);
+        
+        Ok(())
+    }
+
+    /// Enable/disable a peer
+    pub fn set_peer_enabled(
+        ctx: Context<SetPeerEnabled>,
+        dst_eid: u32,
+        enabled: bool,
+    ) -> Result<()> {
+        let peer = &mut ctx.accounts.peer_config;
+        peer.enabled = enabled;
+        
+        msg!("🔧 Peer EID {} enabled: {}", dst_eid, enabled);
+        
+        Ok(())
+    }
+
+    /// Require inbound messages from a peer to be executed in nonce order
+    pub fn set_peer_ordered_execution(
+        ctx: Context<SetPeerOrderedExecution>,
+        dst_eid: u32,
+        ordered_execution: bool,
+    ) -> Result<()> {
+        let peer = &mut ctx.accounts.peer_config;
+        peer.ordered_execution = ordered_execution;
+        
+        msg!("🔧 Peer EID {} ordered execution: {}", dst_eid, ordered_execution);
+        
+        Ok(())
+    }
+
+    /// Restrict which executors may deliver messages from a peer; an empty
+    /// list allows any executor
+    pub fn set_peer_executors(
+        ctx: Context<SetPeerExecutors>,
+        dst_eid: u32,
+        executors: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            executors.len() <= MAX_PEER_EXECUTORS,
+            OftError::TooManyExecutors
+        );
+        
+        let peer = &mut ctx.accounts.peer_config;
+        peer.executors = executors;
+        
+        msg!("🔧 Peer EID {} executors: {:?}", dst_eid, peer.executors);
+        
+        Ok(())
+    }
+
+    /// Set the options enforced on every message of `msg_type` sent to a
+    /// peer; caller `extra_options` are appended to them
+    pub fn set_enforced_options(
+        ctx: Context<SetEnforcedOptions>,
+        dst_eid: u32,
+        msg_type: u8,
+        options: Vec<u8>,
+    ) -> Result<()> {
+        require!(
+            options.len() <= MAX_ENFORCED_OPTIONS_LEN,
+            OftError::InvalidOptions
+        );
+        options::parse(&options)?;
+        
+        let enforced = &mut ctx.accounts.peer_config.enforced_options;
+        match msg_type {
+            MSG_TYPE_SEND => enforced.send = options,
+            MSG_TYPE_SEND_AND_CALL => enforced.send_and_call = options,
+            _ => return err!(OftError::InvalidMessageType),
+        }
+        
+        msg!("🔧 Enforced options set for EID {} (msg type {})", dst_eid, msg_type);
+        
+        Ok(())
+    }
+
+    /// Configure or remove (`None`) the rate limit for one direction of a peer
+    ///
+    /// Amounts already in flight are decayed to now and carried over.
+    pub fn set_rate_limit(
+        ctx: Context<SetRateLimit>,
+        dst_eid: u32,
+        direction: RateLimitDirection,
+        params: Option<RateLimitParams>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let peer = &mut ctx.accounts.peer_config;
+        let rate_limit = match direction {
+            RateLimitDirection::Outbound => &mut peer.outbound_rate_limit,
+            RateLimitDirection::Inbound => &mut peer.inbound_rate_limit,
+        };
+        
+        *rate_limit = match params {
+            Some(params) => Some(RateLimit::configure(params, *rate_limit, now)?),
+            None => None,
+        };
+        
+        msg!("🔧 {:?} rate limit for EID {}: {:?}", direction, dst_eid, params);
+        
+        Ok(())
+    }
+
+    /// Amount that can currently be bridged in one direction of a peer
+    /// (`u64::MAX` when no limit is configured)
+    pub fn get_rate_limit_capacity(
+        ctx: Context<GetRateLimitCapacity>,
+        _dst_eid: u32,
+        direction: RateLimitDirection,
+    ) -> Result<u64> {
+        Ok(ctx.accounts.peer_config.rate_limit_capacity(
+            direction,
+            Clock::get()?.unix_timestamp,
+        ))
+    }
+
+    /// Point the OFT at a different LayerZero endpoint program
+    /// Timelocked while a timelock delay is set
+    pub fn set_endpoint_program(
+        ctx: Context<TimelockedAdmin>,
+        endpoint_program: Pubkey,
+    ) -> Result<()> {
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::SetEndpointProgram { endpoint_program },
+            &ctx.accounts.admin,
+        )?;
+        
+        let config = &mut ctx.accounts.oft_config;
+        let old_endpoint = config.endpoint_program;
+        config.endpoint_program = endpoint_program;
+        
+        msg!("🔧 Endpoint changed from {} to {}", old_endpoint, endpoint_program);
+        
+        Ok(())
+    }
+
+    /// Set the delay between scheduling and executing timelocked actions
+    ///
+    /// With no delay (the initial state) timelocked actions run instantly;
+    /// once set, changing the delay is itself timelocked.
+    pub fn set_timelock_delay(
+        ctx: Context<TimelockedAdmin>,
+        delay: i64,
+    ) -> Result<()> {
+        require!(
+            (0..=MAX_TIMELOCK_DELAY).contains(&delay),
+            OftError::InvalidTimelockDelay
+        );
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::SetTimelockDelay { delay },
+            &ctx.accounts.admin,
+        )?;
+        
+        let config = &mut ctx.accounts.oft_config;
+        config.timelock_delay = delay;
+        
+        msg!("⏳ Timelock delay set to {}s", delay);
+        
+        Ok(())
+    }
+
+    /// Queue a timelocked action, executable once the current delay elapses
+    pub fn schedule_operation(
+        ctx: Context<ScheduleOperation>,
+        action: TimelockAction,
+    ) -> Result<()> {
+        let config = &ctx.accounts.oft_config;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let operation = &mut ctx.accounts.timelock_operation;
+        operation.action = action.clone();
+        operation.scheduled_at = now;
+        operation.bump = ctx.bumps.timelock_operation;
+        let eta = operation.ready_at(config.timelock_delay)?;
+        
+        emit!(OperationScheduled {
+            id: action.id(),
+            action: action.clone(),
+            eta,
+        });
+        
+        msg!("⏳ Scheduled {:?} (ETA: {})", action, eta);
+        
+        Ok(())
+    }
+
+    /// Drop a queued timelocked action before it is executed
+    pub fn cancel_operation(ctx: Context<CancelOperation>) -> Result<()> {
+        let action = ctx.accounts.timelock_operation.action.clone();
+        
+        emit!(OperationCancelled {
+            id: action.id(),
+            action: action.clone(),
+        });
+        
+        msg!("⏳ Cancelled {:?}", action);
+        
+        Ok(())
+    }
+
+    /// Grant `role` to `account`
+    ///
+    /// The endpoint knows a single delegate, so granting `Role::Delegate`
+    /// replaces the current delegate and registers the new one at the
+    /// endpoint; remaining accounts are then the endpoint's `set_delegate`
+    /// accounts. Timelocked while a timelock delay is set.
+    pub fn grant_role<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateRole<'info>>,
+        role: Role,
+        account: Pubkey,
+    ) -> Result<()> {
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::GrantRole { role, account },
+            &ctx.accounts.admin,
+        )?;
+        
+        let roles = &mut ctx.accounts.roles;
+        if role == Role::Delegate {
+            roles.members.retain(|member| member.role != Role::Delegate);
+        }
+        roles.grant(role, account)?;
+        
+        if role == Role::Delegate {
+            ctx.accounts.set_endpoint_delegate(ctx.remaining_accounts, account)?;
+        }
+        
+        emit!(RoleGranted {
+            role,
+            account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("🔑 Granted {:?} to {}", role, account);
+        
+        Ok(())
+    }
+
+    /// Revoke `role` from `account`
+    ///
+    /// Revoking `Role::Delegate` clears the delegate at the endpoint;
+    /// remaining accounts are then the endpoint's `set_delegate` accounts.
+    /// Timelocked while a timelock delay is set.
+    pub fn revoke_role<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateRole<'info>>,
+        role: Role,
+        account: Pubkey,
+    ) -> Result<()> {
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::RevokeRole { role, account },
+            &ctx.accounts.admin,
+        )?;
+        
+        ctx.accounts.roles.revoke(role, account)?;
+        
+        if role == Role::Delegate {
+            ctx.accounts.set_endpoint_delegate(ctx.remaining_accounts, Pubkey::default())?;
+        }
+        
+        emit!(RoleRevoked {
+            role,
+            account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("🔑 Revoked {:?} from {}", role, account);
+        
+        Ok(())
+    }
+
+    /// Propose a new admin; the role moves only once they accept
+    ///
+    /// Proposing again replaces any pending proposal. Timelocked while a
+    /// timelock delay is set.
+    pub fn propose_admin(
+        ctx: Context<ProposeAdmin>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        consume_timelock(
+            &ctx.accounts.oft_config,
+            ctx.accounts.timelock_operation.as_ref(),
+            TimelockAction::ProposeAdmin { new_admin },
+            &ctx.accounts.admin,
+        )?;
+        
+        let config = &mut ctx.accounts.oft_config;
+        config.pending_admin = Some(new_admin);
+        
+        emit!(AdminTransferProposed {
+            admin: config.admin,
+            pending_admin: new_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("👑 Admin transfer proposed from {} to {}", config.admin, new_admin);
+        
+        Ok(())
+    }
+
+    /// Accept a pending admin transfer, signed by the proposed admin
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        let old_admin = config.admin;
+        config.admin = ctx.accounts.pending_admin.key();
+        config.pending_admin = None;
+        
+        emit!(AdminTransferred {
+            old_admin,
+            new_admin: config.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("👑 Admin transferred from {} to {}", old_admin, config.admin);
+        
+        Ok(())
+    }
+
+    /// Withdraw a pending admin transfer
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        let config = &mut ctx.accounts.oft_config;
+        let pending_admin = config.pending_admin.take().ok_or(OftError::NoPendingAdmin)?;
+        
+        emit!(AdminTransferCancelled {
+            admin: config.admin,
+            pending_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("👑 Admin transfer to {} cancelled", pending_admin);
+        
+        Ok(())
+    }
+
+    /// Grow a config created before the current layout and create the role
+    /// registry for it
+    ///
+    /// Appended fields read as zero, except the token program (taken from
+    /// the mint's owner), `local_eid` and the decimals, which are set here.
+    pub fn migrate_config(
+        ctx: Context<MigrateConfig>,
+        local_eid: u32,
+        shared_decimals: u8,
+    ) -> Result<()> {
+        let info = ctx.accounts.oft_config.to_account_info();
+        let space = 8 + OftConfig::INIT_SPACE;
+        require!(info.data_len() < space, OftError::AlreadyMigrated);
+        
+        {
+            // Only the original prefix (admin, mint) is readable before growing
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() >= 72 && data[..8] == *OftConfig::DISCRIMINATOR,
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            require_keys_eq!(
+                Pubkey::new_from_array(data[8..40].try_into().unwrap()),
+                ctx.accounts.admin.key(),
+                OftError::Unauthorized
+            );
+            require_keys_eq!(
+                Pubkey::new_from_array(data[40..72].try_into().unwrap()),
+                ctx.accounts.mint.key(),
+                ErrorCode::ConstraintAddress
+            );
+        }
+        grow_account(&info, &ctx.accounts.admin, &ctx.accounts.system_program, space)?;
+        
+        let mut config = OftConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        config.token_program = *ctx.accounts.mint.to_account_info().owner;
+        config.local_eid = local_eid;
+        config.set_decimals(ctx.accounts.mint.decimals, shared_decimals)?;
+        config.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        
+        ctx.accounts.roles.bump = ctx.bumps.roles;
+        
+        msg!("✅ OFT config migrated");
+        msg!("   Local EID: {}", local_eid);
+        msg!("   Shared decimals: {}", shared_decimals);
+        
+        Ok(())
+    }
+
+    /// Grow a peer created before the current layout; appended fields read
+    /// as zero (no nonce, options, rate limits, fee or executors)
+    pub fn migrate_peer(ctx: Context<MigratePeer>, eid: u32) -> Result<()> {
+        let info = ctx.accounts.peer_config.to_account_info();
+        let space = 8 + PeerConfig::INIT_SPACE;
+        require!(info.data_len() < space, OftError::AlreadyMigrated);
+        require!(
+            info.try_borrow_data()?.get(..8) == Some(PeerConfig::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        grow_account(&info, &ctx.accounts.admin, &ctx.accounts.system_program, space)?;
+        
+        msg!("✅ Peer for EID {} migrated", eid);
+        
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Account Structures
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OftConfig::INIT_SPACE,
+        seeds = [b"oft_config"],
+        bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"roles"],
+        bump
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = LOCAL_DECIMALS,
+        mint::authority = oft_config,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeToken2022<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OftConfig::INIT_SPACE,
+        seeds = [b"oft_config"],
+        bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"roles"],
+        bump
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = LOCAL_DECIMALS,
+        mint::authority = oft_config,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = oft_config,
+        extensions::metadata_pointer::metadata_address = mint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAdapter<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OftConfig::INIT_SPACE,
+        seeds = [b"oft_config"],
+        bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"roles"],
+        bump
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    /// Existing mint bridged by the adapter
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = oft_config,
+        token::token_program = token_program,
+        seeds = [b"escrow"],
+        bump
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterOApp<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump,
+        has_one = admin
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    /// Pays for the endpoint's OApp registry account
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// CHECK: Must be oft_config.endpoint_program
+    #[account(address = oft_config.endpoint_program)]
+    pub endpoint_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct SetPeer<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::PeerManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PeerConfig::INIT_SPACE,
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// Matured timelock operation for this action; required while a
+    /// timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32, send_param: SendParam)]
+pub struct Send<'info> {
+    #[account(
+        mut,
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    #[account(
+        mut,
+        address = oft_config.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Lock/unlock mode only
+    #[account(
+        mut,
+        address = oft_config.escrow
+    )]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Required when the peer charges a bridge fee
+    #[account(
+        mut,
+        address = oft_config.fee_vault
+    )]
+    pub fee_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = from.mint == mint.key(),
+        constraint = from.owner == sender.key()
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Token owner; also pays the LayerZero messaging fee
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    
+    /// Blocklist entry for the sender; must not exist
+    /// CHECK: PDA checked by seeds
+    #[account(
+        seeds = [b"blocked", sender.key().as_ref()],
+        bump
+    )]
+    pub sender_blocked: AccountInfo<'info>,
+    
+    /// Blocklist entry for `send_param.to`; must not exist
+    /// CHECK: PDA checked by seeds
+    #[account(
+        seeds = [b"blocked".as_ref(), &send_param.to],
+        bump
+    )]
+    pub recipient_blocked: AccountInfo<'info>,
+    
+    #[account(address = oft_config.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// LayerZero endpoint program
+    /// CHECK: Validated against oft_config.endpoint_program
+    #[account(address = oft_config.endpoint_program)]
+    pub endpoint_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(origin: Origin, guid: [u8; 32])]
+pub struct LzReceive<'info> {
+    #[account(
+        mut,
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"peer", &origin.src_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + InboundNonce::INIT_SPACE,
+        seeds = [b"inbound_nonce".as_ref(), &origin.src_eid.to_le_bytes(), &origin.sender],
+        bump
+    )]
+    pub inbound_nonce: Account<'info, InboundNonce>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ReceivedMessage::INIT_SPACE,
+        seeds = [b"received".as_ref(), &guid],
+        bump
+    )]
+    pub received_message: Account<'info, ReceivedMessage>,
+    
+    /// Holds the message if it cannot be delivered in non-blocking mode
+    /// CHECK: PDA checked by seeds; only created when the message fails
+    #[account(
+        mut,
+        seeds = [b"failed".as_ref(), &guid],
+        bump
+    )]
+    pub failed_message: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = oft_config.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Lock/unlock mode only
+    #[account(
+        mut,
+        address = oft_config.escrow
+    )]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Recipient decoded from the message
+    /// CHECK: Checked against the message's sendTo in the instruction
+    pub to_address: AccountInfo<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = to_address,
+        associated_token::token_program = token_program
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Blocklist entry for the recipient, if any
+    /// CHECK: PDA checked by seeds
+    #[account(
+        seeds = [b"blocked", to_address.key().as_ref()],
+        bump
+    )]
+    pub recipient_blocked: AccountInfo<'info>,
+    
+    /// Required when the recipient is blocked
+    #[account(
+        mut,
+        address = oft_config.quarantine
+    )]
+    pub quarantine: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Executor delivering the message; pays for the replay-protection
+    /// accounts and the recipient's token account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// LayerZero endpoint program, invoked to clear the verified payload
+    /// CHECK: Must be oft_config.endpoint_program
+    #[account(address = oft_config.endpoint_program)]
+    pub endpoint_program: AccountInfo<'info>,
+    
+    #[account(address = oft_config.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct RetryMessage<'info> {
+    #[account(
+        mut,
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [b"failed".as_ref(), &failed_message.guid],
+        bump = failed_message.bump
+    )]
+    pub failed_message: Account<'info, FailedMessage>,
+    
+    #[account(
+        mut,
+        seeds = [b"peer", &failed_message.src_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    /// Paid for the failed message account
+    /// CHECK: Must be failed_message.payer
+    #[account(mut, address = failed_message.payer)]
+    pub rent_recipient: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = oft_config.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Lock/unlock mode only
+    #[account(
+        mut,
+        address = oft_config.escrow
+    )]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Must be failed_message.recipient
+    #[account(address = failed_message.recipient)]
+    pub to_address: AccountInfo<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = to_address,
+        associated_token::token_program = token_program
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Blocklist entry for the recipient, if any
+    /// CHECK: PDA checked by seeds
+    #[account(
+        seeds = [b"blocked", to_address.key().as_ref()],
+        bump
+    )]
+    pub recipient_blocked: AccountInfo<'info>,
+    
+    /// Required when the recipient is blocked
+    #[account(
+        mut,
+        address = oft_config.quarantine
+    )]
+    pub quarantine: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// LayerZero endpoint program, invoked for send-and-call messages
+    /// CHECK: Must be oft_config.endpoint_program
+    #[account(address = oft_config.endpoint_program)]
+    pub endpoint_program: AccountInfo<'info>,
+    
+    #[account(address = oft_config.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct ClearMessage<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump,
+        has_one = admin
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [b"failed".as_ref(), &failed_message.guid],
+        bump = failed_message.bump
+    )]
+    pub failed_message: Account<'info, FailedMessage>,
+    
+    /// Paid for the failed message account
+    /// CHECK: Must be failed_message.payer
+    #[account(mut, address = failed_message.payer)]
+    pub rent_recipient: AccountInfo<'info>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump,
+        has_one = admin
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [b"failed".as_ref(), &failed_message.guid],
+        bump = failed_message.bump
+    )]
+    pub failed_message: Account<'info, FailedMessage>,
+    
+    #[account(
+        mut,
+        seeds = [b"peer", &failed_message.src_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    /// Paid for the failed message account
+    /// CHECK: Must be failed_message.payer
+    #[account(mut, address = failed_message.payer)]
+    pub rent_recipient: AccountInfo<'info>,
+    
+    /// Pays the LayerZero messaging fee
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// CHECK: Must be oft_config.endpoint_program
+    #[account(address = oft_config.endpoint_program)]
+    pub endpoint_program: AccountInfo<'info>,
+    
+    /// Matured timelock operation for a refund to anyone but the recipient;
+    /// required while a timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
+}
+
+#[derive(Accounts)]
+pub struct LzReceiveTypes<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(address = oft_config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(send_param: SendParam)]
+pub struct QuoteSend<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"peer", &send_param.dst_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    #[account(address = oft_config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: LayerZero endpoint for fee quote
+    #[account(address = oft_config.endpoint_program)]
+    pub endpoint_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(send_param: SendParam)]
+pub struct QuoteOft<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"peer", &send_param.dst_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    #[account(address = oft_config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token account the transfer would be sent from, if it exists yet
+    #[account(
+        constraint = from.mint == mint.key(),
+        constraint = from.owner == sender.key()
+    )]
+    pub from: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Sender, the owner of `from`; does not sign a quote
+    pub sender: AccountInfo<'info>,
+    
+    /// Blocklist entry for the sender; must not exist to send
+    /// CHECK: PDA checked by seeds
+    #[account(
+        seeds = [b"blocked", sender.key().as_ref()],
+        bump
+    )]
+    pub sender_blocked: AccountInfo<'info>,
+    
+    /// Blocklist entry for the recipient; must not exist to send
+    /// CHECK: PDA checked by seeds
+    #[account(
+        seeds = [b"blocked".as_ref(), &send_param.to],
+        bump
+    )]
+    pub recipient_blocked: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::FeeManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(address = oft_config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = oft_config,
+        token::token_program = token_program,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(address = oft_config.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct SetPeerFee<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::FeeManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// Matured timelock operation for this action; required while a
+    /// timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::FeeManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(address = oft_config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        address = oft_config.fee_vault
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = to.mint == mint.key()
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(address = oft_config.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeQuarantine<'info> {
+    #[account(
+        mut,
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::Compliance, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(address = oft_config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = oft_config,
+        token::token_program = token_program,
+        seeds = [b"quarantine"],
+        bump
+    )]
+    pub quarantine: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(address = oft_config.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: [u8; 32])]
+pub struct BlockAddress<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::Compliance, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BlockedAddress::INIT_SPACE,
+        seeds = [b"blocked".as_ref(), &address],
+        bump
+    )]
+    pub blocked_address: Account<'info, BlockedAddress>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: [u8; 32])]
+pub struct UnblockAddress<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::Compliance, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"blocked".as_ref(), &address],
+        bump = blocked_address.bump
+    )]
+    pub blocked_address: Account<'info, BlockedAddress>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseQuarantined<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::Compliance, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(address = oft_config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        address = oft_config.quarantine
+    )]
+    pub quarantine: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = to.mint == mint.key()
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(address = oft_config.token_program)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
     )]
     pub oft_config: Account<'info, OftConfig>,
     
     #[account(
-        init,
-        payer = payer,
-        mint::decimals = SHARED_DECIMALS,
-        mint::authority = oft_config,
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::Pauser, &authority.key()) @ OftError::Unauthorized
     )]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    pub roles: Account<'info, RoleRegistry>,
     
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(dst_eid: u32)]
-pub struct SetPeer<'info> {
+pub struct SetNonBlocking<'info> {
     #[account(
+        mut,
         seeds = [b"oft_config"],
         bump = oft_config.bump,
         has_one = admin
     )]
     pub oft_config: Account<'info, OftConfig>,
     
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct SetPeerEnabled<'info> {
     #[account(
-        init,
-        payer = admin,
-        space = 8 + PeerConfig::INIT_SPACE,
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::PeerManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        mut,
         seeds = [b"peer", &dst_eid.to_le_bytes()],
-        bump
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct SetPeerAddress<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::PeerManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump = peer_config.bump
     )]
     pub peer_config: Account<'info, PeerConfig>,
     
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub authority: Signer<'info>,
     
-    pub system_program: Program<'info, System>,
+    /// Matured timelock operation for this action; required while a
+    /// timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
 }
 
 #[derive(Accounts)]
-pub struct Send<'info> {
+#[instruction(dst_eid: u32)]
+pub struct ClosePeer<'info> {
     #[account(
-        mut,
         seeds = [b"oft_config"],
         bump = oft_config.bump
     )]
     pub oft_config: Account<'info, OftConfig>,
     
     #[account(
-        seeds = [b"peer", &oft_config.bump.to_le_bytes()], // Will be validated in instruction
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::PeerManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
         bump = peer_config.bump
     )]
     pub peer_config: Account<'info, PeerConfig>,
     
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// Matured timelock operation for this action; required while a
+    /// timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct SetPeerOrderedExecution<'info> {
     #[account(
-        mut,
-        address = oft_config.mint
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::PeerManager, &authority.key()) @ OftError::Unauthorized
     )]
-    pub mint: Account<'info, Mint>,
+    pub roles: Account<'info, RoleRegistry>,
     
     #[account(
         mut,
-        constraint = from.mint == mint.key(),
-        constraint = from.owner == sender.key()
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump = peer_config.bump
     )]
-    pub from: Account<'info, TokenAccount>,
+    pub peer_config: Account<'info, PeerConfig>,
     
-    pub sender: Signer<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct SetPeerExecutors<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::PeerManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
     
-    /// LayerZero endpoint program
-    /// CHECK: Validated against oft_config.endpoint_program
-    #[account(address = oft_config.endpoint_program)]
-    pub endpoint_program: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+    
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct LzReceive<'info> {
+#[instruction(dst_eid: u32)]
+pub struct SetEnforcedOptions<'info> {
     #[account(
-        mut,
         seeds = [b"oft_config"],
         bump = oft_config.bump
     )]
     pub oft_config: Account<'info, OftConfig>,
     
     #[account(
-        seeds = [b"peer", &peer_config.eid.to_le_bytes()],
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::PeerManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(
+        mut,
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
         bump = peer_config.bump
     )]
     pub peer_config: Account<'info, PeerConfig>,
     
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct SetRateLimit<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has_role(&oft_config, Role::RateLimitManager, &authority.key()) @ OftError::Unauthorized
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
     #[account(
         mut,
-        address = oft_config.mint
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump = peer_config.bump
     )]
-    pub mint: Account<'info, Mint>,
+    pub peer_config: Account<'info, PeerConfig>,
     
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(dst_eid: u32)]
+pub struct GetRateLimitCapacity<'info> {
+    #[account(
+        seeds = [b"peer", &dst_eid.to_le_bytes()],
+        bump = peer_config.bump
+    )]
+    pub peer_config: Account<'info, PeerConfig>,
+}
+
+#[derive(Accounts)]
+pub struct TimelockedAdmin<'info> {
     #[account(
         mut,
-        constraint = to.mint == mint.key()
+        seeds = [b"oft_config"],
+        bump = oft_config.bump,
+        has_one = admin
     )]
-    pub to: Account<'info, TokenAccount>,
+    pub oft_config: Account<'info, OftConfig>,
     
-    /// LayerZero endpoint program (only endpoint can call lz_receive)
-    /// CHECK: Must be oft_config.endpoint_program
-    #[account(address = oft_config.endpoint_program)]
-    pub endpoint_program: Signer<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// Matured timelock operation for this action; required while a
+    /// timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
+}
+
+#[derive(Accounts)]
+#[instruction(action: TimelockAction)]
+pub struct ScheduleOperation<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump,
+        has_one = admin
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + TimelockOperation::INIT_SPACE,
+        seeds = [b"timelock".as_ref(), &action.id()],
+        bump
+    )]
+    pub timelock_operation: Account<'info, TimelockOperation>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct QuoteSend<'info> {
+pub struct CancelOperation<'info> {
     #[account(
         seeds = [b"oft_config"],
-        bump = oft_config.bump
+        bump = oft_config.bump,
+        has_one = admin
     )]
     pub oft_config: Account<'info, OftConfig>,
     
-    /// CHECK: LayerZero endpoint for fee quote
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"timelock".as_ref(), &timelock_operation.action.id()],
+        bump = timelock_operation.bump
+    )]
+    pub timelock_operation: Account<'info, TimelockOperation>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(
+        seeds = [b"oft_config"],
+        bump = oft_config.bump,
+        has_one = admin
+    )]
+    pub oft_config: Account<'info, OftConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"roles"],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// LayerZero endpoint program, invoked when the delegate changes
+    /// CHECK: Must be oft_config.endpoint_program
     #[account(address = oft_config.endpoint_program)]
     pub endpoint_program: AccountInfo<'info>,
+    
+    /// Matured timelock operation for this action; required while a
+    /// timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
+}
+
+impl<'info> UpdateRole<'info> {
+    /// Register `delegate` at the endpoint, signing as the OApp
+    fn set_endpoint_delegate(
+        &self,
+        endpoint_accounts: &[AccountInfo<'info>],
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let config = &self.oft_config;
+        let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+        endpoint::set_delegate(
+            &self.endpoint_program,
+            config.key(),
+            endpoint_accounts,
+            seeds,
+            EndpointSetDelegateParams { delegate },
+        )
+    }
 }
 
 #[derive(Accounts)]
-pub struct SetPaused<'info> {
+pub struct ProposeAdmin<'info> {
     #[account(
         mut,
         seeds = [b"oft_config"],
@@ -446,40 +2910,94 @@ pub struct SetPaused<'info> {
     )]
     pub oft_config: Account<'info, OftConfig>,
     
+    #[account(mut)]
     pub admin: Signer<'info>,
+    
+    /// Matured timelock operation for this action; required while a
+    /// timelock delay is set
+    #[account(mut)]
+    pub timelock_operation: Option<Account<'info, TimelockOperation>>,
 }
 
 #[derive(Accounts)]
-#[instruction(dst_eid: u32)]
-pub struct SetPeerEnabled<'info> {
+pub struct AcceptAdmin<'info> {
     #[account(
+        mut,
         seeds = [b"oft_config"],
         bump = oft_config.bump,
-        has_one = admin
+        constraint = oft_config.pending_admin == Some(pending_admin.key()) @ OftError::NotPendingAdmin
     )]
     pub oft_config: Account<'info, OftConfig>,
     
+    pub pending_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
     #[account(
         mut,
-        seeds = [b"peer", &dst_eid.to_le_bytes()],
-        bump = peer_config.bump
+        seeds = [b"oft_config"],
+        bump = oft_config.bump,
+        has_one = admin
     )]
-    pub peer_config: Account<'info, PeerConfig>,
+    pub oft_config: Account<'info, OftConfig>,
     
     pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct TransferAdmin<'info> {
+pub struct MigrateConfig<'info> {
+    /// Config in an older layout, so not deserialized
+    /// CHECK: Discriminator, admin and mint are checked by the instruction
     #[account(
         mut,
+        seeds = [b"oft_config"],
+        bump,
+        owner = crate::ID
+    )]
+    pub oft_config: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"roles"],
+        bump
+    )]
+    pub roles: Account<'info, RoleRegistry>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(eid: u32)]
+pub struct MigratePeer<'info> {
+    #[account(
         seeds = [b"oft_config"],
         bump = oft_config.bump,
         has_one = admin
     )]
     pub oft_config: Account<'info, OftConfig>,
     
+    /// Peer in an older layout, so not deserialized
+    /// CHECK: Discriminator is checked by the instruction
+    #[account(
+        mut,
+        seeds = [b"peer", &eid.to_le_bytes()],
+        bump,
+        owner = crate::ID
+    )]
+    pub peer_config: UncheckedAccount<'info>,
+    
+    #[account(mut)]
     pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 // ============================================================================
@@ -496,6 +3014,74 @@ pub struct OftConfig {
     pub total_bridged_in: u64,
     pub total_bridged_out: u64,
     pub bump: u8,
+    // Fields below were appended to the original layout; configs created
+    // before them are grown by `migrate_config`
+    /// Proposed admin, who must accept before the role moves
+    pub pending_admin: Option<Pubkey>,
+    /// SPL Token or Token-2022 program owning the mint
+    pub token_program: Pubkey,
+    /// LayerZero EID of the chain this OFT is deployed on
+    /// (e.g. `SOLANA_MAINNET_EID` or `SOLANA_DEVNET_EID`)
+    pub local_eid: u32,
+    pub mode: OftMode,
+    /// Token account holding locked tokens in lock/unlock mode
+    pub escrow: Pubkey,
+    /// Token account collecting bridge fees, once created
+    pub fee_vault: Pubkey,
+    /// Token account holding inbound transfers to blocked recipients, once
+    /// created
+    pub quarantine: Pubkey,
+    /// Store undeliverable inbound messages instead of rejecting them
+    pub non_blocking: bool,
+    /// Seconds between scheduling and executing timelocked actions
+    pub timelock_delay: i64,
+    pub shared_decimals: u8,
+    /// 10^(mint decimals - shared_decimals)
+    pub ld2sd_rate: u64,
+}
+
+impl OftConfig {
+    /// Set the shared decimals and the local-to-shared conversion rate
+    pub fn set_decimals(&mut self, local_decimals: u8, shared_decimals: u8) -> Result<()> {
+        require!(
+            shared_decimals <= local_decimals,
+            OftError::InvalidSharedDecimals
+        );
+        
+        // Mints with more than 19 decimals above shared would overflow the rate
+        self.ld2sd_rate = 10u64
+            .checked_pow((local_decimals - shared_decimals) as u32)
+            .ok_or(OftError::InvalidSharedDecimals)?;
+        self.shared_decimals = shared_decimals;
+        
+        Ok(())
+    }
+    
+    /// Convert a local-decimals amount to shared decimals, dropping dust
+    pub fn to_sd(&self, amount_ld: u64) -> u64 {
+        amount_ld / self.ld2sd_rate
+    }
+    
+    /// Convert a shared-decimals amount to local decimals
+    pub fn to_ld(&self, amount_sd: u64) -> Result<u64> {
+        amount_sd
+            .checked_mul(self.ld2sd_rate)
+            .ok_or(OftError::Overflow.into())
+    }
+    
+    /// Round `amount_ld` down to what is representable in shared decimals
+    pub fn remove_dust(&self, amount_ld: u64) -> u64 {
+        amount_ld - amount_ld % self.ld2sd_rate
+    }
+}
+
+/// How the OFT moves tokens in and out of circulation on Solana
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OftMode {
+    /// Native OFT: burn on send, mint on receive
+    MintBurn,
+    /// Adapter over an existing mint: lock in escrow on send, release on receive
+    LockUnlock,
 }
 
 #[account]
@@ -505,6 +3091,302 @@ pub struct PeerConfig {
     pub address: [u8; 32],
     pub enabled: bool,
     pub bump: u8,
+    // Fields below were appended to the original layout; peers created
+    // before them are grown by `migrate_peer`
+    /// Inbound messages must be executed in nonce order
+    pub ordered_execution: bool,
+    /// Nonce of the last message sent to this peer
+    pub outbound_nonce: u64,
+    pub enforced_options: EnforcedOptions,
+    /// Limits on tokens sent to / received from this peer; `None` is unlimited
+    pub outbound_rate_limit: Option<RateLimit>,
+    pub inbound_rate_limit: Option<RateLimit>,
+    /// Bridge fee on sends to this peer, in basis points
+    pub fee_bps: u16,
+    /// Executors allowed to deliver messages from this peer; empty allows any
+    #[max_len(MAX_PEER_EXECUTORS)]
+    pub executors: Vec<Pubkey>,
+}
+
+impl PeerConfig {
+    /// Bridge fee charged on sending `amount_ld` to this peer
+    pub fn bridge_fee(&self, amount_ld: u64) -> u64 {
+        (amount_ld as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+    
+    /// Currently available capacity in `direction`
+    pub fn rate_limit_capacity(&self, direction: RateLimitDirection, now: i64) -> u64 {
+        let rate_limit = match direction {
+            RateLimitDirection::Outbound => &self.outbound_rate_limit,
+            RateLimitDirection::Inbound => &self.inbound_rate_limit,
+        };
+        rate_limit.map_or(u64::MAX, |rate_limit| rate_limit.capacity(now))
+    }
+    
+    /// Record `amount` against the rate limit in `direction`, failing if it
+    /// exceeds the available capacity
+    pub fn consume_rate_limit(
+        &mut self,
+        direction: RateLimitDirection,
+        amount: u64,
+        now: i64,
+    ) -> Result<()> {
+        let (rate_limit, error) = match direction {
+            RateLimitDirection::Outbound => {
+                (&mut self.outbound_rate_limit, OftError::OutboundRateLimitExceeded)
+            }
+            RateLimitDirection::Inbound => {
+                (&mut self.inbound_rate_limit, OftError::InboundRateLimitExceeded)
+            }
+        };
+        let Some(rate_limit) = rate_limit else {
+            return Ok(());
+        };
+        
+        if amount > rate_limit.capacity(now) {
+            return Err(error.into());
+        }
+        rate_limit.amount_in_flight = rate_limit.amount_in_flight_at(now) + amount;
+        // A clock running backwards must not replay decay already applied
+        rate_limit.last_updated = rate_limit.last_updated.max(now);
+        
+        Ok(())
+    }
+}
+
+/// Windowed rate limit: up to `limit` tokens in flight, with the amount in
+/// flight decaying linearly to zero over `window` seconds
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub window: u64,
+    pub amount_in_flight: u64,
+    pub last_updated: i64,
+}
+
+impl RateLimit {
+    /// Rate limit for `params`, carrying over what is still in flight under
+    /// the `current` one
+    pub fn configure(params: RateLimitParams, current: Option<RateLimit>, now: i64) -> Result<Self> {
+        require!(params.window > 0, OftError::InvalidRateLimit);
+        
+        Ok(match current {
+            Some(current) => RateLimit {
+                limit: params.limit,
+                window: params.window,
+                amount_in_flight: current.amount_in_flight_at(now),
+                last_updated: current.last_updated.max(now),
+            },
+            None => RateLimit {
+                limit: params.limit,
+                window: params.window,
+                amount_in_flight: 0,
+                last_updated: now,
+            },
+        })
+    }
+    
+    /// Amount still in flight at `now` after linear decay
+    pub fn amount_in_flight_at(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.last_updated).max(0) as u128;
+        let decay = (self.limit as u128 * elapsed / self.window as u128).min(u64::MAX as u128);
+        self.amount_in_flight.saturating_sub(decay as u64)
+    }
+    
+    /// Amount that can be bridged at `now`
+    pub fn capacity(&self, now: i64) -> u64 {
+        self.limit.saturating_sub(self.amount_in_flight_at(now))
+    }
+}
+
+/// Type 3 options enforced on messages to a peer, per message type
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, Default)]
+pub struct EnforcedOptions {
+    #[max_len(MAX_ENFORCED_OPTIONS_LEN)]
+    pub send: Vec<u8>,
+    #[max_len(MAX_ENFORCED_OPTIONS_LEN)]
+    pub send_and_call: Vec<u8>,
+}
+
+impl EnforcedOptions {
+    /// Options handed to the endpoint for `send_param`: the enforced options
+    /// for its message type combined with the caller's `extra_options`
+    pub fn combine(&self, send_param: &SendParam) -> Result<Vec<u8>> {
+        let enforced = if send_param.compose_msg.is_empty() {
+            &self.send
+        } else {
+            &self.send_and_call
+        };
+        options::combine(enforced, &send_param.extra_options)
+    }
+}
+
+/// Last executed inbound nonce for a (src_eid, sender) path
+#[account]
+#[derive(InitSpace)]
+pub struct InboundNonce {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub last_nonce: u64,
+    pub bump: u8,
+}
+
+/// Marks an inbound message GUID as consumed
+#[account]
+#[derive(InitSpace)]
+pub struct ReceivedMessage {
+    pub executed: bool,
+    pub bump: u8,
+}
+
+/// An inbound message that could not be delivered in non-blocking mode.
+/// Sized to its compose payload when created.
+#[account]
+pub struct FailedMessage {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub recipient: Pubkey,
+    pub amount_ld: u64,
+    /// `composeFrom | composeMsg` for send-and-call messages, empty otherwise
+    pub compose_msg: Vec<u8>,
+    pub reason: DeliveryFailure,
+    /// Paid for this account; refunded its rent once the message is resolved
+    pub payer: Pubkey,
+    pub failed_at: i64,
+    pub bump: u8,
+}
+
+impl FailedMessage {
+    /// A composed message may only be refunded to its `composeFrom`
+    pub fn check_refund_to(&self, refund_to: [u8; 32]) -> Result<()> {
+        if let Some(compose_from) = self.compose_msg.get(..32) {
+            require!(compose_from == refund_to, OftError::InvalidRefundRecipient);
+        }
+        Ok(())
+    }
+    
+    /// Refunding a plain message anywhere but its recipient is timelocked
+    pub fn refund_needs_timelock(&self, refund_to: [u8; 32]) -> bool {
+        self.compose_msg.is_empty() && refund_to != self.recipient.to_bytes()
+    }
+}
+
+/// Why an inbound message could not be delivered
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryFailure {
+    Paused,
+    PeerDisabled,
+    RateLimitExceeded,
+    InsufficientEscrow,
+}
+
+impl DeliveryFailure {
+    /// Error raised for this failure when the message cannot be stored
+    pub fn error(self) -> OftError {
+        match self {
+            DeliveryFailure::Paused => OftError::Paused,
+            DeliveryFailure::PeerDisabled => OftError::PeerDisabled,
+            DeliveryFailure::RateLimitExceeded => OftError::InboundRateLimitExceeded,
+            DeliveryFailure::InsufficientEscrow => OftError::InsufficientEscrow,
+        }
+    }
+}
+
+/// Blocklist entry; its existence blocks `address`
+#[account]
+#[derive(InitSpace)]
+pub struct BlockedAddress {
+    /// Solana pubkey or bytes32 address on another chain
+    pub address: [u8; 32],
+    pub blocked_at: i64,
+    pub bump: u8,
+}
+
+/// A scheduled timelocked action, executable once the timelock delay in
+/// force at execution has passed since `scheduled_at`
+#[account]
+#[derive(InitSpace)]
+pub struct TimelockOperation {
+    pub action: TimelockAction,
+    pub scheduled_at: i64,
+    pub bump: u8,
+}
+
+impl TimelockOperation {
+    /// When the operation becomes executable under `timelock_delay`
+    pub fn ready_at(&self, timelock_delay: i64) -> Result<i64> {
+        self.scheduled_at
+            .checked_add(timelock_delay)
+            .ok_or(OftError::Overflow.into())
+    }
+}
+
+/// Operational roles granted by the admin. The admin holds every role.
+#[account]
+#[derive(InitSpace)]
+pub struct RoleRegistry {
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub members: Vec<RoleMember>,
+    pub bump: u8,
+}
+
+impl RoleRegistry {
+    /// Whether `account` may act as `role`
+    pub fn has_role(&self, config: &OftConfig, role: Role, account: &Pubkey) -> bool {
+        *account == config.admin
+            || self.members.contains(&RoleMember { role, account: *account })
+    }
+    
+    /// Add a grant; granting an existing role is a no-op
+    pub fn grant(&mut self, role: Role, account: Pubkey) -> Result<()> {
+        let member = RoleMember { role, account };
+        if self.members.contains(&member) {
+            return Ok(());
+        }
+        require!(self.members.len() < MAX_ROLE_MEMBERS, OftError::RoleRegistryFull);
+        self.members.push(member);
+        
+        Ok(())
+    }
+    
+    /// Remove a grant
+    pub fn revoke(&mut self, role: Role, account: Pubkey) -> Result<()> {
+        let member = RoleMember { role, account };
+        let index = self
+            .members
+            .iter()
+            .position(|granted| *granted == member)
+            .ok_or(OftError::RoleNotGranted)?;
+        self.members.swap_remove(index);
+        
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoleMember {
+    pub role: Role,
+    pub account: Pubkey,
+}
+
+/// Operational roles below the admin
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Can pause the OFT (but not unpause it)
+    Pauser,
+    /// Can add, update, enable and close peers and set their options
+    PeerManager,
+    /// Can configure per-peer rate limits
+    RateLimitManager,
+    /// Can manage bridge fees
+    FeeManager,
+    /// LayerZero delegate, configuring send/receive libraries and DVNs at
+    /// the endpoint
+    Delegate,
+    /// Can manage the blocklist and quarantined tokens
+    Compliance,
 }
 
 // ============================================================================
@@ -522,6 +3404,75 @@ pub struct SendParam {
     pub oft_cmd: Vec<u8>,
 }
 
+/// Admin actions that go through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, PartialEq, Eq)]
+pub enum TimelockAction {
+    SetPeer { eid: u32, address: [u8; 32] },
+    SetPeerAddress { eid: u32, address: [u8; 32] },
+    ClosePeer { eid: u32 },
+    SetEndpointProgram { endpoint_program: Pubkey },
+    ProposeAdmin { new_admin: Pubkey },
+    SetTimelockDelay { delay: i64 },
+    SetPeerFee { eid: u32, fee_bps: u16 },
+    GrantRole { role: Role, account: Pubkey },
+    RevokeRole { role: Role, account: Pubkey },
+    Refund { guid: [u8; 32], refund_to: [u8; 32] },
+}
+
+impl TimelockAction {
+    /// Identifier of the action, seeding its `TimelockOperation` PDA
+    pub fn id(&self) -> [u8; 32] {
+        use anchor_lang::solana_program::keccak;
+        
+        keccak::hash(&self.try_to_vec().unwrap_or_default()).to_bytes()
+    }
+}
+
+/// A fee taken from an OFT transfer, like the EVM `OFTFeeDetail`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OftFeeDetail {
+    pub fee_amount_ld: u64,
+    pub description: String,
+}
+
+/// Amounts that can be sent, like the EVM `OFTLimit`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct OftLimit {
+    pub min_amount_ld: u64,
+    pub max_amount_ld: u64,
+}
+
+/// Outcome of a transfer, like the EVM `OFTReceipt`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct OftReceipt {
+    /// Taken from the sender, fees included
+    pub amount_sent_ld: u64,
+    /// Credited on the destination, in local decimals
+    pub amount_received_ld: u64,
+}
+
+/// Result of `quote_oft`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OftQuote {
+    pub limit: OftLimit,
+    pub fee_details: Vec<OftFeeDetail>,
+    pub receipt: OftReceipt,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitDirection {
+    Outbound,
+    Inbound,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct RateLimitParams {
+    /// Maximum amount in flight, in local decimals
+    pub limit: u64,
+    /// Seconds for a full `limit` to decay
+    pub window: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct Origin {
     pub src_eid: u32,
@@ -535,6 +3486,38 @@ pub struct MessagingFee {
     pub lz_token_fee: u64,
 }
 
+/// Account required by `lz_receive`, as reported by `lz_receive_types`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LzAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl LzAccount {
+    fn readonly(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: false }
+    }
+    
+    fn writable(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: true }
+    }
+    
+    fn signer(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: true, is_writable: true }
+    }
+}
+
+impl From<AccountMeta> for LzAccount {
+    fn from(meta: AccountMeta) -> Self {
+        Self {
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
 pub struct SendReceipt {
     pub guid: [u8; 32],
@@ -547,24 +3530,168 @@ pub struct SendReceipt {
 // ============================================================================
 
 #[event]
-pub struct SendEvent {
-    pub guid: [u8; 32],
-    pub src_eid: u32,
-    pub dst_eid: u32,
-    pub to: [u8; 32],
-    pub amount_ld: u64,
-    pub sender: Pubkey,
+pub struct SendEvent {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub dst_eid: u32,
+    pub nonce: u64,
+    pub to: [u8; 32],
+    pub amount_ld: u64,
+    /// Bridge fee collected on top of `amount_ld`
+    pub fee_ld: u64,
+    pub sender: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReceiveEvent {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub dst_eid: u32,
+    pub to: Pubkey,
+    pub amount_ld: u64,
+    /// What reached `to` after any Token-2022 transfer fee
+    pub amount_received_ld: u64,
+    pub nonce: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PeerSet {
+    pub eid: u32,
+    pub address: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PeerAddressUpdated {
+    pub eid: u32,
+    pub old_address: [u8; 32],
+    pub new_address: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PeerClosed {
+    pub eid: u32,
+    pub address: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub to: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AddressBlocked {
+    pub address: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AddressUnblocked {
+    pub address: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TransferQuarantined {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub recipient: Pubkey,
+    pub amount_ld: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QuarantineReleased {
+    pub to: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MessageFailed {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub nonce: u64,
+    pub reason: DeliveryFailure,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MessageCleared {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub amount_ld: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MessageRefunded {
+    pub guid: [u8; 32],
+    pub refund_guid: [u8; 32],
+    pub dst_eid: u32,
+    pub to: [u8; 32],
+    pub amount_ld: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OperationScheduled {
+    pub id: [u8; 32],
+    pub action: TimelockAction,
+    /// Earliest execution under the current delay
+    pub eta: i64,
+}
+
+#[event]
+pub struct OperationCancelled {
+    pub id: [u8; 32],
+    pub action: TimelockAction,
+}
+
+#[event]
+pub struct OperationExecuted {
+    pub id: [u8; 32],
+    pub action: TimelockAction,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct ReceiveEvent {
-    pub guid: [u8; 32],
-    pub src_eid: u32,
-    pub dst_eid: u32,
-    pub to: Pubkey,
-    pub amount_ld: u64,
-    pub nonce: u64,
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
     pub timestamp: i64,
 }
 
@@ -572,44 +3699,444 @@ pub struct ReceiveEvent {
 // Helper Functions
 // ============================================================================
 
-fn encode_oft_message(msg_type: u8, to: [u8; 32], amount: u64) -> Vec<u8> {
-    let mut message = Vec::with_capacity(41);
-    message.push(msg_type);
-    message.extend_from_slice(&to);
-    message.extend_from_slice(&amount.to_be_bytes());
-    message
+/// Build the message `send` hands to the endpoint for `send_param`
+fn build_oft_message(send_param: &SendParam, amount_sd: u64, sender: Pubkey) -> Vec<u8> {
+    msg_codec::encode(
+        send_param.to,
+        amount_sd,
+        sender.to_bytes(),
+        &send_param.compose_msg,
+    )
+}
+
+/// How `send` splits an amount: fees first, then dust removal
+struct Debit {
+    bridge_fee_ld: u64,
+    /// Token-2022 transfer fee withheld on the way into the escrow
+    transfer_fee_ld: u64,
+    /// Amount bridged to the destination
+    amount_sent_ld: u64,
+    /// Amount taken from the sender, fees included; the dust stays
+    amount_debited_ld: u64,
+}
+
+impl Debit {
+    /// The amount bridged, after fees and dust removal, must reach `min_amount_ld`
+    fn check_slippage(&self, min_amount_ld: u64) -> Result<()> {
+        require!(
+            self.amount_sent_ld >= min_amount_ld,
+            OftError::SlippageExceeded
+        );
+        Ok(())
+    }
+
+    fn fee_details(&self) -> Vec<OftFeeDetail> {
+        let mut details = Vec::new();
+        if self.bridge_fee_ld > 0 {
+            details.push(OftFeeDetail {
+                fee_amount_ld: self.bridge_fee_ld,
+                description: "Bridge fee".to_string(),
+            });
+        }
+        if self.transfer_fee_ld > 0 {
+            details.push(OftFeeDetail {
+                fee_amount_ld: self.transfer_fee_ld,
+                description: "Token transfer fee".to_string(),
+            });
+        }
+        details
+    }
+}
+
+/// What `send` debits for `amount_ld` sent to `peer`. The bridge fee is
+/// taken first; when locking, a Token-2022 transfer fee is taken on the way
+/// into the escrow, so only what arrives there is bridged. Dust stays with
+/// the sender.
+fn debit_view(
+    config: &OftConfig,
+    peer: &PeerConfig,
+    mint: &AccountInfo,
+    amount_ld: u64,
+) -> Result<Debit> {
+    let bridge_fee_ld = peer.bridge_fee(amount_ld);
+    let amount_after_fee_ld = amount_ld - bridge_fee_ld;
+    let transfer_fee_ld = match config.mode {
+        OftMode::MintBurn => 0,
+        OftMode::LockUnlock => token_extensions::transfer_fee(mint, amount_after_fee_ld)?,
+    };
+    let amount_sent_ld = config.remove_dust(amount_after_fee_ld - transfer_fee_ld);
+    let amount_moved_ld = match config.mode {
+        OftMode::MintBurn => amount_sent_ld,
+        OftMode::LockUnlock => token_extensions::gross_amount(mint, amount_sent_ld)?,
+    };
+    
+    Ok(Debit {
+        bridge_fee_ld,
+        transfer_fee_ld,
+        amount_sent_ld,
+        amount_debited_ld: bridge_fee_ld
+            .checked_add(amount_moved_ld)
+            .ok_or(OftError::Overflow)?,
+    })
+}
+
+/// What `send` to `peer` accepts right now from a sender holding
+/// `balance_ld`, if known. The maximum is what sending the whole balance
+/// bridges, after the bridge fee and, when locking, the transfer fee, capped
+/// by the outbound rate limit. Nothing can be sent while `send` would be
+/// refused.
+fn send_limit(
+    config: &OftConfig,
+    peer: &PeerConfig,
+    mint: &AccountInfo,
+    balance_ld: Option<u64>,
+    blocked: bool,
+    now: i64,
+) -> Result<OftLimit> {
+    let max_amount_ld = if config.paused || !peer.enabled || blocked {
+        0
+    } else {
+        let capacity =
+            config.remove_dust(peer.rate_limit_capacity(RateLimitDirection::Outbound, now));
+        match balance_ld {
+            Some(balance_ld) => debit_view(config, peer, mint, balance_ld)?
+                .amount_sent_ld
+                .min(capacity),
+            None => capacity,
+        }
+    };
+    
+    Ok(OftLimit {
+        min_amount_ld: config.ld2sd_rate,
+        max_amount_ld,
+    })
+}
+
+/// Accounts of `lz_receive` for a message, in the order of `LzReceive`
+/// followed by the endpoint's `clear` and `send_compose` accounts
+fn lz_receive_accounts(
+    config: &OftConfig,
+    oft_config: Pubkey,
+    origin: &Origin,
+    guid: &[u8; 32],
+    message: &[u8],
+) -> Result<Vec<LzAccount>> {
+    let payer = Pubkey::default();
+
+    let oft_message = msg_codec::decode(message)?;
+    let recipient = Pubkey::new_from_array(oft_message.send_to);
+
+    let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+    let peer_config = pda(&[b"peer", &origin.src_eid.to_le_bytes()]);
+    let inbound_nonce = pda(&[
+        b"inbound_nonce",
+        &origin.src_eid.to_le_bytes(),
+        &origin.sender,
+    ]);
+    let received_message = pda(&[b"received", guid]);
+    let failed_message = pda(&[b"failed", guid]);
+    let recipient_blocked = pda(&[b"blocked", recipient.as_ref()]);
+
+    let mut accounts = vec![
+        LzAccount::writable(oft_config),
+        LzAccount::writable(peer_config),
+        LzAccount::writable(inbound_nonce),
+        LzAccount::writable(received_message),
+        LzAccount::writable(failed_message),
+        LzAccount::writable(config.mint),
+        // Optional accounts are passed as the program ID when absent
+        match config.mode {
+            OftMode::MintBurn => LzAccount::readonly(crate::ID),
+            OftMode::LockUnlock => LzAccount::writable(config.escrow),
+        },
+        LzAccount::readonly(recipient),
+        LzAccount::writable(get_associated_token_address_with_program_id(
+            &recipient,
+            &config.mint,
+            &config.token_program,
+        )),
+        LzAccount::readonly(recipient_blocked),
+        if config.quarantine == Pubkey::default() {
+            LzAccount::readonly(crate::ID)
+        } else {
+            LzAccount::writable(config.quarantine)
+        },
+        LzAccount::signer(payer),
+        LzAccount::readonly(config.endpoint_program),
+        LzAccount::readonly(config.token_program),
+        LzAccount::readonly(anchor_lang::system_program::ID),
+        LzAccount::readonly(anchor_spl::associated_token::ID),
+    ];
+
+    accounts.extend(
+        endpoint::clear_accounts(
+            &config.endpoint_program,
+            &oft_config,
+            origin.src_eid,
+            &origin.sender,
+            origin.nonce,
+        )
+        .into_iter()
+        .map(LzAccount::from),
+    );
+
+    if oft_message.compose_msg.is_some() {
+        accounts.extend(
+            endpoint::send_compose_accounts(
+                &config.endpoint_program,
+                &oft_config,
+                &payer,
+                &recipient,
+                guid,
+                0,
+            )
+            .into_iter()
+            .map(LzAccount::from),
+        );
+    }
+
+    Ok(accounts)
+}
+
+/// Why an inbound transfer of `amount_ld` cannot be delivered right now,
+/// if it cannot
+fn delivery_failure(
+    config: &OftConfig,
+    peer: &PeerConfig,
+    escrow: Option<&InterfaceAccount<TokenAccount>>,
+    amount_ld: u64,
+    now: i64,
+) -> Result<Option<DeliveryFailure>> {
+    if config.paused {
+        return Ok(Some(DeliveryFailure::Paused));
+    }
+    if !peer.enabled {
+        return Ok(Some(DeliveryFailure::PeerDisabled));
+    }
+    if amount_ld > peer.rate_limit_capacity(RateLimitDirection::Inbound, now) {
+        return Ok(Some(DeliveryFailure::RateLimitExceeded));
+    }
+    if config.mode == OftMode::LockUnlock {
+        let escrow = escrow.ok_or(OftError::InvalidEscrow)?;
+        if escrow.amount < amount_ld {
+            return Ok(Some(DeliveryFailure::InsufficientEscrow));
+        }
+    }
+    
+    Ok(None)
+}
+
+/// Mint or release `amount_ld` to `to`, checking that the supply or the
+/// escrow moved by exactly that amount. Any Token-2022 transfer fee on a
+/// release is withheld from the recipient's side. `hook_accounts` are the
+/// accounts of the mint's transfer hook, if any.
+///
+/// Returns the amount that reached `to`.
+fn credit<'info>(
+    config: &Account<'info, OftConfig>,
+    mint: &mut InterfaceAccount<'info, Mint>,
+    escrow: Option<&mut InterfaceAccount<'info, TokenAccount>>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount_ld: u64,
+) -> Result<u64> {
+    let seeds: &[&[u8]] = &[b"oft_config", &[config.bump]];
+    let signer = &[seeds];
+    
+    match config.mode {
+        OftMode::MintBurn => {
+            let supply_before = mint.supply;
+            
+            token_interface::mint_to(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    MintTo {
+                        mint: mint.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: config.to_account_info(),
+                    },
+                    signer,
+                ),
+                amount_ld,
+            )?;
+            
+            mint.reload()?;
+            require!(
+                mint.supply.checked_sub(supply_before) == Some(amount_ld),
+                OftError::SupplyInvariantViolated
+            );
+            
+            Ok(amount_ld)
+        }
+        OftMode::LockUnlock => {
+            let escrow = escrow.ok_or(OftError::InvalidEscrow)?;
+            let escrowed_before = escrow.amount;
+            let transfer_fee_ld = token_extensions::transfer_fee(&mint.to_account_info(), amount_ld)?;
+            
+            token_extensions::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: escrow.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: config.to_account_info(),
+                    },
+                    signer,
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                amount_ld,
+                mint.decimals,
+            )?;
+            
+            escrow.reload()?;
+            require!(
+                escrowed_before.checked_sub(escrow.amount) == Some(amount_ld),
+                OftError::SupplyInvariantViolated
+            );
+            
+            Ok(amount_ld - transfer_fee_ld)
+        }
+    }
+}
+
+/// Create the `FailedMessage` PDA at `account`, sized to `failed`, funded
+/// by `payer`. Lamports already sent to the address are kept, so the
+/// account cannot be blocked by pre-funding it.
+fn store_failed_message<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    bump: u8,
+    failed: &FailedMessage,
+) -> Result<()> {
+    use anchor_lang::system_program::{allocate, assign, transfer, Allocate, Assign, Transfer};
+    
+    let mut data = Vec::new();
+    failed.try_serialize(&mut data)?;
+    
+    let seeds: &[&[u8]] = &[b"failed", &failed.guid, &[bump]];
+    let signer = &[seeds];
+    
+    let top_up = Rent::get()?
+        .minimum_balance(data.len())
+        .saturating_sub(account.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate { account_to_allocate: account.clone() },
+            signer,
+        ),
+        data.len() as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign { account_to_assign: account.clone() },
+            signer,
+        ),
+        &crate::ID,
+    )?;
+    
+    account.try_borrow_mut_data()?.copy_from_slice(&data);
+    
+    Ok(())
 }
 
-fn decode_oft_message(message: &[u8]) -> Result<(u8, [u8; 32], u64)> {
-    require!(message.len() >= 41, OftError::InvalidMessage);
+/// Gate a timelocked action. It runs instantly while no timelock delay is
+/// set; otherwise `operation` must hold `action` and be past its ETA, and
+/// is closed to `rent_destination` once used.
+fn consume_timelock<'info>(
+    config: &OftConfig,
+    operation: Option<&Account<'info, TimelockOperation>>,
+    action: TimelockAction,
+    rent_destination: &AccountInfo<'info>,
+) -> Result<()> {
+    if config.timelock_delay == 0 {
+        return Ok(());
+    }
     
-    let msg_type = message[0];
+    let operation = operation.ok_or(OftError::TimelockRequired)?;
+    require!(operation.action == action, OftError::TimelockActionMismatch);
+    // A delay raised after scheduling applies to pending operations too
+    require!(
+        Clock::get()?.unix_timestamp >= operation.ready_at(config.timelock_delay)?,
+        OftError::TimelockNotReady
+    );
     
-    let mut to = [0u8; 32];
-    to.copy_from_slice(&message[1..33]);
+    emit!(OperationExecuted {
+        id: action.id(),
+        action,
+    });
     
-    let mut amount_bytes = [0u8; 8];
-    amount_bytes.copy_from_slice(&message[33..41]);
-    let amount = u64::from_be_bytes(amount_bytes);
+    operation.close(rent_destination.clone())
+}
+
+/// Resize `account` to `space`, zero-filling the new bytes, with `payer`
+/// covering the extra rent
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    let top_up = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.resize(space)?;
     
-    Ok((msg_type, to, amount))
+    Ok(())
 }
 
+/// Whether a blocklist PDA exists; it can only be created by `block_address`
+fn is_blocked(blocked_address: &AccountInfo) -> bool {
+    !blocked_address.data_is_empty()
+}
+
+/// GUID of a packet as computed by the LayerZero V2 endpoint:
+/// `keccak256(nonce | src_eid | sender | dst_eid | receiver)`, integers big-endian
 fn generate_guid(
-    sender: &Pubkey,
+    nonce: u64,
+    src_eid: u32,
+    sender: [u8; 32],
     dst_eid: u32,
-    amount: u64,
-    timestamp: u64,
+    receiver: [u8; 32],
 ) -> [u8; 32] {
     use anchor_lang::solana_program::keccak;
     
-    let mut data = Vec::new();
-    data.extend_from_slice(sender.as_ref());
-    data.extend_from_slice(&dst_eid.to_le_bytes());
-    data.extend_from_slice(&amount.to_le_bytes());
-    data.extend_from_slice(&timestamp.to_le_bytes());
-    
-    keccak::hash(&data).to_bytes()
+    keccak::hashv(&[
+        &nonce.to_be_bytes(),
+        &src_eid.to_be_bytes(),
+        &sender,
+        &dst_eid.to_be_bytes(),
+        &receiver,
+    ])
+    .to_bytes()
 }
 
 // ============================================================================
@@ -644,4 +4171,703 @@ pub enum OftError {
     
     #[msg("Unauthorized operation")]
     Unauthorized,
+    
+    #[msg("Missing or invalid LayerZero endpoint accounts")]
+    InvalidEndpointAccounts,
+    
+    #[msg("Unexpected response from LayerZero endpoint")]
+    InvalidEndpointResponse,
+    
+    #[msg("Shared decimals must be at most local decimals and at most 19 below them")]
+    InvalidSharedDecimals,
+    
+    #[msg("Message has already been executed")]
+    MessageAlreadyExecuted,
+    
+    #[msg("Message nonce out of order")]
+    InvalidNonce,
+    
+    #[msg("GUID does not match the message origin")]
+    InvalidGuid,
+    
+    #[msg("Invalid LayerZero options")]
+    InvalidOptions,
+    
+    #[msg("Rate limit window must be non-zero")]
+    InvalidRateLimit,
+    
+    #[msg("Outbound rate limit exceeded")]
+    OutboundRateLimitExceeded,
+    
+    #[msg("Inbound rate limit exceeded")]
+    InboundRateLimitExceeded,
+    
+    #[msg("Escrow account missing or invalid")]
+    InvalidEscrow,
+    
+    #[msg("Escrow balance too low to release tokens")]
+    InsufficientEscrow,
+    
+    #[msg("Token supply did not change by the bridged amount")]
+    SupplyInvariantViolated,
+    
+    #[msg("Transfer-hook accounts missing or invalid")]
+    InvalidTransferHookAccounts,
+    
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+    
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    
+    #[msg("Role registry is full")]
+    RoleRegistryFull,
+    
+    #[msg("Role is not granted to this account")]
+    RoleNotGranted,
+    
+    #[msg("Timelock delay out of range")]
+    InvalidTimelockDelay,
+    
+    #[msg("Action must be scheduled through the timelock")]
+    TimelockRequired,
+    
+    #[msg("Timelock operation is for a different action")]
+    TimelockActionMismatch,
+    
+    #[msg("Timelock delay has not elapsed")]
+    TimelockNotReady,
+    
+    #[msg("Bridge fee above the maximum")]
+    InvalidFee,
+    
+    #[msg("Fee vault missing or invalid")]
+    InvalidFeeVault,
+    
+    #[msg("Executor is not allowed for this peer")]
+    UnauthorizedExecutor,
+    
+    #[msg("Too many executors for one peer")]
+    TooManyExecutors,
+    
+    #[msg("Address is blocked")]
+    AddressBlocked,
+    
+    #[msg("Quarantine account missing or invalid")]
+    InvalidQuarantine,
+    
+    #[msg("Composed messages must be refunded to their sender")]
+    InvalidRefundRecipient,
+    
+    #[msg("Account already has the current layout")]
+    AlreadyMigrated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: OftMode, local_decimals: u8, shared_decimals: u8) -> OftConfig {
+        let mut config = OftConfig {
+            admin: Pubkey::new_unique(),
+            pending_admin: None,
+            mint: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
+            endpoint_program: Pubkey::new_unique(),
+            local_eid: SOLANA_DEVNET_EID,
+            mode,
+            escrow: match mode {
+                OftMode::MintBurn => Pubkey::default(),
+                OftMode::LockUnlock => Pubkey::new_unique(),
+            },
+            fee_vault: Pubkey::default(),
+            quarantine: Pubkey::default(),
+            paused: false,
+            non_blocking: false,
+            timelock_delay: 0,
+            total_bridged_in: 0,
+            total_bridged_out: 0,
+            shared_decimals: 0,
+            ld2sd_rate: 0,
+            bump: 255,
+        };
+        config.set_decimals(local_decimals, shared_decimals).unwrap();
+        config
+    }
+
+    fn peer(eid: u32) -> PeerConfig {
+        PeerConfig {
+            eid,
+            address: [1u8; 32],
+            enabled: true,
+            ordered_execution: false,
+            outbound_nonce: 0,
+            enforced_options: EnforcedOptions::default(),
+            outbound_rate_limit: None,
+            inbound_rate_limit: None,
+            fee_bps: 0,
+            executors: Vec::new(),
+            bump: 255,
+        }
+    }
+
+    /// Run `f` with a placeholder account, for paths that never read it
+    fn with_unused_account<T>(f: impl FnOnce(&AccountInfo) -> T) -> T {
+        let (key, owner) = (Pubkey::new_unique(), Pubkey::default());
+        let (mut lamports, mut data) = (0u64, [0u8; 0]);
+        f(&AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0))
+    }
+
+    /// `lz_receive` accounts as `LzReceive` lays them out, with keys derived
+    /// independently of `lz_receive_accounts`, then the endpoint accounts
+    fn expected_lz_receive_accounts(
+        config: &OftConfig,
+        oft_config: Pubkey,
+        origin: &Origin,
+        guid: &[u8; 32],
+        recipient: Pubkey,
+        composed: bool,
+    ) -> Vec<LzAccount> {
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+        let src_eid = origin.src_eid.to_le_bytes();
+        let payer = Pubkey::default();
+
+        let mut metas = crate::accounts::LzReceive {
+            oft_config,
+            peer_config: pda(&[b"peer", &src_eid]),
+            inbound_nonce: pda(&[b"inbound_nonce", &src_eid, &origin.sender]),
+            received_message: pda(&[b"received", guid]),
+            failed_message: pda(&[b"failed", guid]),
+            mint: config.mint,
+            escrow: (config.mode == OftMode::LockUnlock).then_some(config.escrow),
+            to_address: recipient,
+            to: get_associated_token_address_with_program_id(
+                &recipient,
+                &config.mint,
+                &config.token_program,
+            ),
+            recipient_blocked: pda(&[b"blocked", recipient.as_ref()]),
+            quarantine: (config.quarantine != Pubkey::default()).then_some(config.quarantine),
+            payer,
+            endpoint_program: config.endpoint_program,
+            token_program: config.token_program,
+            system_program: anchor_lang::system_program::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+        }
+        .to_account_metas(None);
+
+        metas.extend(endpoint::clear_accounts(
+            &config.endpoint_program,
+            &oft_config,
+            origin.src_eid,
+            &origin.sender,
+            origin.nonce,
+        ));
+        if composed {
+            metas.extend(endpoint::send_compose_accounts(
+                &config.endpoint_program,
+                &oft_config,
+                &payer,
+                &recipient,
+                guid,
+                0,
+            ));
+        }
+
+        metas.into_iter().map(LzAccount::from).collect()
+    }
+
+    fn assert_discovery_matches_lz_receive(mode: OftMode, composed: bool, quarantine: bool) {
+        let mut config = config(mode, 9, 6);
+        if quarantine {
+            config.quarantine = Pubkey::new_unique();
+        }
+        let oft_config = Pubkey::new_unique();
+        let origin = Origin { src_eid: ETHEREUM_MAINNET_EID, sender: [1u8; 32], nonce: 7 };
+        let guid = [9u8; 32];
+        let recipient = Pubkey::new_unique();
+        let message = if composed {
+            msg_codec::encode(recipient.to_bytes(), 1_000, [2u8; 32], b"hello")
+        } else {
+            msg_codec::encode(recipient.to_bytes(), 1_000, [0u8; 32], &[])
+        };
+
+        let discovered =
+            lz_receive_accounts(&config, oft_config, &origin, &guid, &message).unwrap();
+
+        assert_eq!(
+            discovered,
+            expected_lz_receive_accounts(&config, oft_config, &origin, &guid, recipient, composed)
+        );
+    }
+
+    #[test]
+    fn lz_receive_types_matches_lz_receive_for_mint_burn() {
+        assert_discovery_matches_lz_receive(OftMode::MintBurn, false, false);
+    }
+
+    #[test]
+    fn lz_receive_types_matches_lz_receive_for_mint_burn_with_compose() {
+        assert_discovery_matches_lz_receive(OftMode::MintBurn, true, false);
+    }
+
+    #[test]
+    fn lz_receive_types_matches_lz_receive_for_lock_unlock() {
+        assert_discovery_matches_lz_receive(OftMode::LockUnlock, false, true);
+    }
+
+    #[test]
+    fn lz_receive_types_matches_lz_receive_for_lock_unlock_with_compose() {
+        assert_discovery_matches_lz_receive(OftMode::LockUnlock, true, true);
+    }
+
+    #[test]
+    fn lz_receive_types_reports_peer_config_writable() {
+        let config = config(OftMode::LockUnlock, 9, 6);
+        let origin = Origin { src_eid: ETHEREUM_MAINNET_EID, sender: [1u8; 32], nonce: 1 };
+        let message = msg_codec::encode(Pubkey::new_unique().to_bytes(), 1_000, [0u8; 32], &[]);
+
+        let discovered =
+            lz_receive_accounts(&config, Pubkey::new_unique(), &origin, &[7u8; 32], &message)
+                .unwrap();
+
+        // lz_receive records the inbound rate limit on the peer
+        assert!(discovered[1].is_writable);
+    }
+
+    fn rate_limit(limit: u64, window: u64, amount_in_flight: u64, last_updated: i64) -> RateLimit {
+        RateLimit { limit, window, amount_in_flight, last_updated }
+    }
+
+    #[test]
+    fn rate_limit_decays_over_partial_window() {
+        let rate_limit = rate_limit(1_000, 100, 1_000, 0);
+
+        assert_eq!(rate_limit.amount_in_flight_at(25), 750);
+        assert_eq!(rate_limit.capacity(25), 250);
+        assert_eq!(rate_limit.amount_in_flight_at(99), 10);
+    }
+
+    #[test]
+    fn rate_limit_decays_fully_after_window() {
+        let rate_limit = rate_limit(1_000, 100, 1_000, 0);
+
+        assert_eq!(rate_limit.amount_in_flight_at(100), 0);
+        assert_eq!(rate_limit.capacity(100), 1_000);
+        assert_eq!(rate_limit.amount_in_flight_at(i64::MAX), 0);
+        assert_eq!(rate_limit.capacity(i64::MAX), 1_000);
+    }
+
+    #[test]
+    fn consume_rate_limit_checks_capacity() {
+        let mut peer = peer(ETHEREUM_MAINNET_EID);
+        peer.outbound_rate_limit = Some(rate_limit(1_000, 100, 0, 0));
+
+        peer.consume_rate_limit(RateLimitDirection::Outbound, 600, 0).unwrap();
+        assert_eq!(
+            peer.consume_rate_limit(RateLimitDirection::Outbound, 500, 0).unwrap_err(),
+            OftError::OutboundRateLimitExceeded.into()
+        );
+        peer.consume_rate_limit(RateLimitDirection::Outbound, 500, 10).unwrap();
+        assert_eq!(peer.rate_limit_capacity(RateLimitDirection::Outbound, 10), 0);
+
+        // Inbound is unlimited
+        peer.consume_rate_limit(RateLimitDirection::Inbound, u64::MAX, 10).unwrap();
+        assert_eq!(peer.rate_limit_capacity(RateLimitDirection::Inbound, 10), u64::MAX);
+    }
+
+    #[test]
+    fn rate_limit_lowered_below_amount_in_flight() {
+        let current = rate_limit(1_000, 100, 1_000, 0);
+        let params = RateLimitParams { limit: 400, window: 100 };
+        let mut peer = peer(ETHEREUM_MAINNET_EID);
+        peer.inbound_rate_limit = Some(RateLimit::configure(params, Some(current), 0).unwrap());
+
+        // Nothing can be bridged until the excess has decayed at the new rate
+        assert_eq!(peer.rate_limit_capacity(RateLimitDirection::Inbound, 0), 0);
+        assert_eq!(
+            peer.consume_rate_limit(RateLimitDirection::Inbound, 1, 0).unwrap_err(),
+            OftError::InboundRateLimitExceeded.into()
+        );
+        assert_eq!(peer.rate_limit_capacity(RateLimitDirection::Inbound, 150), 0);
+        assert_eq!(peer.rate_limit_capacity(RateLimitDirection::Inbound, 200), 200);
+        assert_eq!(peer.rate_limit_capacity(RateLimitDirection::Inbound, 250), 400);
+    }
+
+    #[test]
+    fn rate_limit_ignores_clock_going_backwards() {
+        let mut peer = peer(ETHEREUM_MAINNET_EID);
+        peer.outbound_rate_limit = Some(rate_limit(1_000, 100, 500, 100));
+
+        // No decay, and no negative decay, before the last update
+        assert_eq!(peer.rate_limit_capacity(RateLimitDirection::Outbound, 50), 500);
+        peer.consume_rate_limit(RateLimitDirection::Outbound, 100, 50).unwrap();
+
+        let rate_limit = peer.outbound_rate_limit.unwrap();
+        assert_eq!(rate_limit.amount_in_flight, 600);
+        assert_eq!(rate_limit.last_updated, 100);
+        assert_eq!(rate_limit.amount_in_flight_at(150), 100);
+    }
+
+    #[test]
+    fn configure_rate_limit_carries_over_amount_in_flight() {
+        let current = rate_limit(1_000, 100, 1_000, 0);
+        let params = RateLimitParams { limit: 2_000, window: 10 };
+
+        let configured = RateLimit::configure(params, Some(current), 40).unwrap();
+
+        assert_eq!(configured.limit, 2_000);
+        assert_eq!(configured.window, 10);
+        assert_eq!(configured.amount_in_flight, 600);
+        assert_eq!(configured.last_updated, 40);
+        // The carried amount decays at the new rate
+        assert_eq!(configured.amount_in_flight_at(42), 200);
+    }
+
+    #[test]
+    fn configure_rate_limit_starts_empty_or_keeps_later_timestamp() {
+        let params = RateLimitParams { limit: 1_000, window: 100 };
+
+        let fresh = RateLimit::configure(params, None, 40).unwrap();
+        assert_eq!((fresh.amount_in_flight, fresh.last_updated), (0, 40));
+
+        let current = rate_limit(1_000, 100, 500, 100);
+        let configured = RateLimit::configure(params, Some(current), 50).unwrap();
+        assert_eq!((configured.amount_in_flight, configured.last_updated), (500, 100));
+    }
+
+    #[test]
+    fn configure_rate_limit_rejects_zero_window() {
+        let params = RateLimitParams { limit: 1_000, window: 0 };
+
+        assert_eq!(
+            RateLimit::configure(params, None, 0).unwrap_err(),
+            OftError::InvalidRateLimit.into()
+        );
+    }
+
+    /// Data of an account written in an older layout and grown to `space`
+    /// the way `grow_account` does
+    fn grown<T: AnchorSerialize>(discriminator: &[u8], old: &T, space: usize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        old.serialize(&mut data).unwrap();
+        data.resize(space, 0);
+        data
+    }
+
+    #[test]
+    fn original_config_layout_reads_after_growing() {
+        #[derive(AnchorSerialize)]
+        struct OriginalOftConfig {
+            admin: Pubkey,
+            mint: Pubkey,
+            endpoint_program: Pubkey,
+            paused: bool,
+            total_bridged_in: u64,
+            total_bridged_out: u64,
+            bump: u8,
+        }
+        let (admin, mint, endpoint_program) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let data = grown(
+            OftConfig::DISCRIMINATOR,
+            &OriginalOftConfig {
+                admin,
+                mint,
+                endpoint_program,
+                paused: true,
+                total_bridged_in: 5,
+                total_bridged_out: 7,
+                bump: 254,
+            },
+            8 + OftConfig::INIT_SPACE,
+        );
+
+        let config = OftConfig::try_deserialize(&mut &data[..]).unwrap();
+
+        assert_eq!(
+            (config.admin, config.mint, config.endpoint_program),
+            (admin, mint, endpoint_program)
+        );
+        assert!(config.paused);
+        assert_eq!((config.total_bridged_in, config.total_bridged_out), (5, 7));
+        assert_eq!(config.bump, 254);
+        assert_eq!(config.pending_admin, None);
+        assert_eq!(config.mode, OftMode::MintBurn);
+        assert_eq!(config.quarantine, Pubkey::default());
+        assert_eq!(config.timelock_delay, 0);
+    }
+
+    #[test]
+    fn original_peer_layout_reads_after_growing() {
+        #[derive(AnchorSerialize)]
+        struct OriginalPeerConfig {
+            eid: u32,
+            address: [u8; 32],
+            enabled: bool,
+            bump: u8,
+        }
+        let data = grown(
+            PeerConfig::DISCRIMINATOR,
+            &OriginalPeerConfig {
+                eid: ETHEREUM_MAINNET_EID,
+                address: [1u8; 32],
+                enabled: true,
+                bump: 253,
+            },
+            8 + PeerConfig::INIT_SPACE,
+        );
+
+        let peer = PeerConfig::try_deserialize(&mut &data[..]).unwrap();
+
+        assert_eq!((peer.eid, peer.address, peer.enabled), (ETHEREUM_MAINNET_EID, [1u8; 32], true));
+        assert_eq!(peer.bump, 253);
+        assert_eq!(peer.outbound_nonce, 0);
+        assert!(peer.enforced_options.send.is_empty());
+        assert!(peer.outbound_rate_limit.is_none());
+        assert_eq!(peer.fee_bps, 0);
+        assert!(peer.executors.is_empty());
+    }
+
+    #[test]
+    fn composed_messages_refund_only_to_compose_from() {
+        let compose_from = [7u8; 32];
+        let mut failed = FailedMessage {
+            guid: [1u8; 32],
+            src_eid: 30101,
+            sender: [1u8; 32],
+            nonce: 1,
+            recipient: Pubkey::new_unique(),
+            amount_ld: 1_000,
+            compose_msg: [compose_from.as_slice(), b"call"].concat(),
+            reason: DeliveryFailure::Paused,
+            payer: Pubkey::new_unique(),
+            failed_at: 0,
+            bump: 255,
+        };
+
+        failed.check_refund_to(compose_from).unwrap();
+        assert_eq!(
+            failed.check_refund_to([8u8; 32]).unwrap_err(),
+            OftError::InvalidRefundRecipient.into()
+        );
+
+        assert!(!failed.refund_needs_timelock(compose_from));
+
+        // Plain messages go to their recipient, anywhere else behind the timelock
+        failed.compose_msg.clear();
+        failed.check_refund_to([8u8; 32]).unwrap();
+        assert!(failed.refund_needs_timelock([8u8; 32]));
+        assert!(!failed.refund_needs_timelock(failed.recipient.to_bytes()));
+    }
+
+    #[test]
+    fn timelock_operation_follows_current_delay() {
+        let operation = TimelockOperation {
+            action: TimelockAction::SetTimelockDelay { delay: 0 },
+            scheduled_at: 1_000,
+            bump: 255,
+        };
+
+        assert_eq!(operation.ready_at(3_600).unwrap(), 4_600);
+        // Raising the delay after scheduling holds the operation back
+        assert_eq!(operation.ready_at(MAX_TIMELOCK_DELAY).unwrap(), 1_000 + MAX_TIMELOCK_DELAY);
+        assert_eq!(operation.ready_at(0).unwrap(), 1_000);
+        assert_eq!(
+            TimelockOperation { scheduled_at: i64::MAX, ..operation }.ready_at(1).unwrap_err(),
+            OftError::Overflow.into()
+        );
+    }
+
+    #[test]
+    fn role_changes_are_distinct_timelock_actions() {
+        let account = Pubkey::new_unique();
+        let grant = TimelockAction::GrantRole { role: Role::Delegate, account };
+
+        assert_ne!(grant.id(), TimelockAction::RevokeRole { role: Role::Delegate, account }.id());
+        assert_ne!(grant.id(), TimelockAction::GrantRole { role: Role::Pauser, account }.id());
+        assert_ne!(
+            grant.id(),
+            TimelockAction::GrantRole { role: Role::Delegate, account: Pubkey::new_unique() }.id()
+        );
+        assert!(grant.try_to_vec().unwrap().len() <= TimelockAction::INIT_SPACE);
+    }
+
+    #[test]
+    fn set_decimals_computes_conversion_rate() {
+        assert_eq!(config(OftMode::MintBurn, 9, 6).ld2sd_rate, 1_000);
+        assert_eq!(config(OftMode::MintBurn, 6, 6).ld2sd_rate, 1);
+        assert_eq!(config(OftMode::MintBurn, 18, 6).ld2sd_rate, 1_000_000_000_000);
+    }
+
+    #[test]
+    fn set_decimals_rejects_shared_above_local() {
+        let mut config = config(OftMode::MintBurn, 9, 6);
+
+        assert_eq!(
+            config.set_decimals(6, 9).unwrap_err(),
+            OftError::InvalidSharedDecimals.into()
+        );
+    }
+
+    #[test]
+    fn set_decimals_rejects_rate_overflow() {
+        let mut config = config(OftMode::MintBurn, 9, 6);
+
+        config.set_decimals(25, 6).unwrap();
+        assert_eq!(config.ld2sd_rate, 10_000_000_000_000_000_000);
+        assert_eq!(
+            config.set_decimals(26, 6).unwrap_err(),
+            OftError::InvalidSharedDecimals.into()
+        );
+        assert_eq!(
+            config.set_decimals(u8::MAX, 0).unwrap_err(),
+            OftError::InvalidSharedDecimals.into()
+        );
+        // Left unchanged by the failed calls
+        assert_eq!((config.shared_decimals, config.ld2sd_rate), (6, 10_000_000_000_000_000_000));
+    }
+
+    #[test]
+    fn to_sd_drops_dust() {
+        let config = config(OftMode::MintBurn, 9, 6);
+
+        assert_eq!(config.to_sd(1_234_567_891), 1_234_567);
+        assert_eq!(config.to_sd(999), 0);
+        assert_eq!(config.to_sd(u64::MAX), u64::MAX / 1_000);
+    }
+
+    #[test]
+    fn to_ld_scales_up_and_checks_overflow() {
+        let config = config(OftMode::MintBurn, 9, 6);
+
+        assert_eq!(config.to_ld(1_234_567).unwrap(), 1_234_567_000);
+        assert_eq!(config.to_ld(0).unwrap(), 0);
+        assert_eq!(
+            config.to_ld(u64::MAX / 1_000 + 1).unwrap_err(),
+            OftError::Overflow.into()
+        );
+    }
+
+    #[test]
+    fn remove_dust_rounds_down_to_shared_decimals() {
+        let config = config(OftMode::MintBurn, 9, 6);
+
+        assert_eq!(config.remove_dust(1_234_567_891), 1_234_567_000);
+        assert_eq!(config.remove_dust(1_234_567_000), 1_234_567_000);
+        assert_eq!(config.remove_dust(999), 0);
+        assert_eq!(config.to_ld(config.to_sd(1_234_567_891)).unwrap(), 1_234_567_000);
+    }
+
+    #[test]
+    fn send_limit_is_bounded_by_balance_after_fees() {
+        let config = config(OftMode::MintBurn, 9, 6);
+        let mut peer = peer(ETHEREUM_MAINNET_EID);
+        peer.fee_bps = 100;
+
+        let limit = with_unused_account(|mint| {
+            send_limit(&config, &peer, mint, Some(10_000_000_999), false, 0).unwrap()
+        });
+
+        assert_eq!(limit.min_amount_ld, 1_000);
+        // 1% bridge fee off the whole balance, then dust
+        assert_eq!(limit.max_amount_ld, 9_900_000_000);
+    }
+
+    #[test]
+    fn send_limit_is_bounded_by_outbound_capacity() {
+        let config = config(OftMode::MintBurn, 9, 6);
+        let mut peer = peer(ETHEREUM_MAINNET_EID);
+        peer.outbound_rate_limit = Some(rate_limit(5_000_000_999, 100, 0, 0));
+
+        let limit = with_unused_account(|mint| {
+            send_limit(&config, &peer, mint, Some(10_000_000_000), false, 0).unwrap()
+        });
+
+        assert_eq!(limit.max_amount_ld, 5_000_000_000);
+    }
+
+    #[test]
+    fn send_limit_without_balance_is_bounded_by_capacity_only() {
+        let config = config(OftMode::MintBurn, 9, 6);
+        let mut peer = peer(ETHEREUM_MAINNET_EID);
+        peer.fee_bps = 100;
+
+        let max = |peer: &PeerConfig| {
+            with_unused_account(|mint| send_limit(&config, peer, mint, None, false, 0).unwrap())
+                .max_amount_ld
+        };
+
+        assert_eq!(max(&peer), config.remove_dust(u64::MAX));
+
+        peer.outbound_rate_limit = Some(rate_limit(5_000_000_999, 100, 0, 0));
+        assert_eq!(max(&peer), 5_000_000_000);
+    }
+
+    #[test]
+    fn send_limit_when_locking_ignores_supply() {
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+
+        let config = config(OftMode::LockUnlock, 9, 6);
+        // Supply minted on Solana says nothing about what a holder can lock
+        let mut data = vec![0u8; MintState::LEN];
+        MintState { supply: 1_000, decimals: 9, is_initialized: true, ..Default::default() }
+            .pack_into_slice(&mut data);
+        let (key, owner) = (config.mint, config.token_program);
+        let mut lamports = 0;
+        let mint = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let limit = send_limit(&config, &peer(ETHEREUM_MAINNET_EID), &mint, Some(10_000_000_000), false, 0)
+            .unwrap();
+
+        assert_eq!(limit.max_amount_ld, 10_000_000_000);
+    }
+
+    #[test]
+    fn send_limit_is_zero_while_send_is_refused() {
+        let mut config = config(OftMode::MintBurn, 9, 6);
+        let mut peer = peer(ETHEREUM_MAINNET_EID);
+        let max = |config: &OftConfig, peer: &PeerConfig, blocked: bool| {
+            with_unused_account(|mint| {
+                send_limit(config, peer, mint, Some(10_000_000_000), blocked, 0)
+                    .unwrap()
+                    .max_amount_ld
+            })
+        };
+
+        assert_eq!(max(&config, &peer, false), 10_000_000_000);
+        assert_eq!(max(&config, &peer, true), 0);
+
+        peer.enabled = false;
+        assert_eq!(max(&config, &peer, false), 0);
+
+        peer.enabled = true;
+        config.paused = true;
+        assert_eq!(max(&config, &peer, false), 0);
+    }
+
+    #[test]
+    fn same_decimals_have_no_dust() {
+        let config = config(OftMode::MintBurn, 6, 6);
+
+        assert_eq!(config.to_sd(1_234_567), 1_234_567);
+        assert_eq!(config.remove_dust(1_234_567), 1_234_567);
+    }
+
+    #[test]
+    fn min_amount_is_compared_after_dust_removal() {
+        let config = config(OftMode::MintBurn, 9, 6);
+        let debit = with_unused_account(|mint| {
+            debit_view(&config, &peer(ETHEREUM_MAINNET_EID), mint, 1_000_000_999).unwrap()
+        });
+
+        assert_eq!(debit.amount_sent_ld, 1_000_000_000);
+        assert_eq!(debit.amount_debited_ld, 1_000_000_000);
+        assert!(debit.check_slippage(1_000_000_000).is_ok());
+        assert_eq!(
+            debit.check_slippage(1_000_000_999).unwrap_err(),
+            OftError::SlippageExceeded.into()
+        );
+    }
 }
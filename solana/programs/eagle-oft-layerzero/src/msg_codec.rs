@@ -0,0 +1,174 @@
+//! OFT message codec, byte-for-byte compatible with LayerZero's `OFTMsgCodec`
+//! used by the EVM `EagleShareOFT`.
+//!
+//! Layout:
+//! `sendTo (bytes32) | amountSD (uint64, big-endian) | [composeFrom (bytes32) | composeMsg]`
+
+use anchor_lang::prelude::*;
+
+use crate::OftError;
+
+const SEND_TO_OFFSET: usize = 32;
+const SEND_AMOUNT_SD_OFFSET: usize = 40;
+
+/// Decoded OFT message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OftMessage {
+    pub send_to: [u8; 32],
+    pub amount_sd: u64,
+    /// `composeFrom | composeMsg`, present only for send-and-call messages
+    pub compose_msg: Option<Vec<u8>>,
+}
+
+/// Encode an OFT message. `compose_from` is only written when `compose_msg`
+/// is non-empty, matching `OFTMsgCodec.encode`.
+pub fn encode(send_to: [u8; 32], amount_sd: u64, compose_from: [u8; 32], compose_msg: &[u8]) -> Vec<u8> {
+    let composed = !compose_msg.is_empty();
+    let mut message = Vec::with_capacity(
+        SEND_AMOUNT_SD_OFFSET + if composed { 32 + compose_msg.len() } else { 0 },
+    );
+
+    message.extend_from_slice(&send_to);
+    message.extend_from_slice(&amount_sd.to_be_bytes());
+    if composed {
+        message.extend_from_slice(&compose_from);
+        message.extend_from_slice(compose_msg);
+    }
+    message
+}
+
+/// Decode an OFT message produced by this program or by `OFTMsgCodec.encode`
+pub fn decode(message: &[u8]) -> Result<OftMessage> {
+    require!(message.len() >= SEND_AMOUNT_SD_OFFSET, OftError::InvalidMessage);
+
+    let mut send_to = [0u8; 32];
+    send_to.copy_from_slice(&message[..SEND_TO_OFFSET]);
+
+    let mut amount_bytes = [0u8; 8];
+    amount_bytes.copy_from_slice(&message[SEND_TO_OFFSET..SEND_AMOUNT_SD_OFFSET]);
+
+    let compose_msg = if is_composed(message) {
+        // composeFrom must be present in full
+        require!(message.len() >= SEND_AMOUNT_SD_OFFSET + 32, OftError::InvalidMessage);
+        Some(message[SEND_AMOUNT_SD_OFFSET..].to_vec())
+    } else {
+        None
+    };
+
+    Ok(OftMessage {
+        send_to,
+        amount_sd: u64::from_be_bytes(amount_bytes),
+        compose_msg,
+    })
+}
+
+/// Whether the message carries a compose payload
+pub fn is_composed(message: &[u8]) -> bool {
+    message.len() > SEND_AMOUNT_SD_OFFSET
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vectors from `OFTMsgCodec.encode(sendTo, amountShared, composeMsg)`,
+    // called by an OFT at 0x70997970C51812dc3A010C7d01b50e0d17dc79C8.
+    // Regenerate with `contracts/mocks/OFTMsgCodecHarness.sol` called from
+    // that address; the codec is `abi.encodePacked(sendTo, amountShared)`,
+    // followed by `composeFrom | composeMsg` when composing.
+    const SEND_TO: &str = "c6e2b3a2e8a1b4f9d1a1f4d0c3b2a1908f7e6d5c4b3a29180706050403020100";
+    const COMPOSE_FROM: &str = "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+    /// `encode(SEND_TO, 1_000_000, "")`
+    const PLAIN: &str = concat!(
+        "c6e2b3a2e8a1b4f9d1a1f4d0c3b2a1908f7e6d5c4b3a29180706050403020100",
+        "00000000000f4240",
+    );
+
+    /// `encode(SEND_TO, 18_446_744_073_709_551_615, 0xdeadbeef)`
+    const COMPOSED: &str = concat!(
+        "c6e2b3a2e8a1b4f9d1a1f4d0c3b2a1908f7e6d5c4b3a29180706050403020100",
+        "ffffffffffffffff",
+        "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8",
+        "deadbeef",
+    );
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn bytes32(s: &str) -> [u8; 32] {
+        hex(s).try_into().unwrap()
+    }
+
+    #[test]
+    fn encodes_plain_message() {
+        let message = encode(bytes32(SEND_TO), 1_000_000, bytes32(COMPOSE_FROM), &[]);
+
+        assert_eq!(message, hex(PLAIN));
+        assert!(!is_composed(&message));
+    }
+
+    #[test]
+    fn decodes_plain_message() {
+        let message = decode(&hex(PLAIN)).unwrap();
+
+        assert_eq!(message.send_to, bytes32(SEND_TO));
+        assert_eq!(message.amount_sd, 1_000_000);
+        assert_eq!(message.compose_msg, None);
+    }
+
+    #[test]
+    fn encodes_composed_message() {
+        let message = encode(
+            bytes32(SEND_TO),
+            u64::MAX,
+            bytes32(COMPOSE_FROM),
+            &hex("deadbeef"),
+        );
+
+        assert_eq!(message, hex(COMPOSED));
+        assert!(is_composed(&message));
+    }
+
+    #[test]
+    fn decodes_composed_message() {
+        let message = decode(&hex(COMPOSED)).unwrap();
+
+        assert_eq!(message.send_to, bytes32(SEND_TO));
+        assert_eq!(message.amount_sd, u64::MAX);
+        assert_eq!(
+            message.compose_msg,
+            Some([hex(COMPOSE_FROM), hex("deadbeef")].concat())
+        );
+    }
+
+    #[test]
+    fn rejects_message_shorter_than_header() {
+        let message = hex(PLAIN);
+
+        for len in [0, 32, 39] {
+            assert_eq!(
+                decode(&message[..len]).unwrap_err(),
+                OftError::InvalidMessage.into()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_compose_from() {
+        let message = hex(COMPOSED);
+
+        for len in [41, 56, 71] {
+            assert_eq!(
+                decode(&message[..len]).unwrap_err(),
+                OftError::InvalidMessage.into()
+            );
+        }
+        // composeFrom alone, with an empty composeMsg, is well formed
+        assert!(decode(&message[..72]).is_ok());
+    }
+}
@@ -0,0 +1,310 @@
+//! LayerZero Type 3 options: parsing and combining with enforced options,
+//! mirroring the EVM `OptionsBuilder` / `OAppOptionsType3`.
+//!
+//! Layout: `type (u16 = 3) | { worker_id (u8) | size (u16) | option_type (u8) | params }*`
+//! where `size` covers `option_type` and `params`. Integers are big-endian.
+
+use anchor_lang::prelude::*;
+
+use crate::OftError;
+
+pub const TYPE_3: u16 = 3;
+
+pub const EXECUTOR_WORKER_ID: u8 = 1;
+pub const DVN_WORKER_ID: u8 = 2;
+
+pub const OPTION_TYPE_LZRECEIVE: u8 = 1;
+pub const OPTION_TYPE_NATIVE_DROP: u8 = 2;
+pub const OPTION_TYPE_LZCOMPOSE: u8 = 3;
+pub const OPTION_TYPE_ORDERED_EXECUTION: u8 = 4;
+
+/// A single worker option
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkerOption {
+    pub worker_id: u8,
+    pub option_type: u8,
+    pub params: Vec<u8>,
+}
+
+/// Decoded executor option
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutorOption {
+    /// Gas (compute units on Solana) and value for `lz_receive`
+    LzReceive { gas: u128, value: u128 },
+    /// Native tokens dropped to `receiver` on the destination
+    NativeDrop { amount: u128, receiver: [u8; 32] },
+    /// Gas and value for the `lz_compose` call at `index`
+    LzCompose { index: u16, gas: u128, value: u128 },
+    OrderedExecution,
+}
+
+/// Parse Type 3 options into worker options. Empty options are valid.
+pub fn parse(options: &[u8]) -> Result<Vec<WorkerOption>> {
+    if options.is_empty() {
+        return Ok(Vec::new());
+    }
+    require!(options_type(options) == Some(TYPE_3), OftError::InvalidOptions);
+
+    let mut parsed = Vec::new();
+    let mut cursor = 2;
+    while cursor < options.len() {
+        require!(cursor + 4 <= options.len(), OftError::InvalidOptions);
+        let worker_id = options[cursor];
+        let size = u16::from_be_bytes([options[cursor + 1], options[cursor + 2]]) as usize;
+        require!(size >= 1, OftError::InvalidOptions);
+
+        let start = cursor + 3;
+        let end = start + size;
+        require!(end <= options.len(), OftError::InvalidOptions);
+
+        parsed.push(WorkerOption {
+            worker_id,
+            option_type: options[start],
+            params: options[start + 1..end].to_vec(),
+        });
+        cursor = end;
+    }
+
+    Ok(parsed)
+}
+
+/// Parse the executor options out of Type 3 options
+pub fn executor_options(options: &[u8]) -> Result<Vec<ExecutorOption>> {
+    parse(options)?
+        .into_iter()
+        .filter(|option| option.worker_id == EXECUTOR_WORKER_ID)
+        .map(|option| decode_executor_option(option.option_type, &option.params))
+        .collect()
+}
+
+/// Combine enforced options with caller options like
+/// `OAppOptionsType3.combineOptions`: either side alone is used as is,
+/// otherwise the caller's worker options are appended to the enforced ones.
+pub fn combine(enforced: &[u8], extra: &[u8]) -> Result<Vec<u8>> {
+    if enforced.is_empty() {
+        return Ok(extra.to_vec());
+    }
+    if extra.is_empty() {
+        return Ok(enforced.to_vec());
+    }
+    require!(options_type(extra) == Some(TYPE_3), OftError::InvalidOptions);
+
+    let mut combined = enforced.to_vec();
+    combined.extend_from_slice(&extra[2..]);
+    Ok(combined)
+}
+
+fn options_type(options: &[u8]) -> Option<u16> {
+    options
+        .get(..2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn decode_executor_option(option_type: u8, params: &[u8]) -> Result<ExecutorOption> {
+    match (option_type, params.len()) {
+        (OPTION_TYPE_LZRECEIVE, 16) => Ok(ExecutorOption::LzReceive {
+            gas: read_u128(&params[..16]),
+            value: 0,
+        }),
+        (OPTION_TYPE_LZRECEIVE, 32) => Ok(ExecutorOption::LzReceive {
+            gas: read_u128(&params[..16]),
+            value: read_u128(&params[16..]),
+        }),
+        (OPTION_TYPE_NATIVE_DROP, 48) => {
+            let mut receiver = [0u8; 32];
+            receiver.copy_from_slice(&params[16..]);
+            Ok(ExecutorOption::NativeDrop {
+                amount: read_u128(&params[..16]),
+                receiver,
+            })
+        }
+        (OPTION_TYPE_LZCOMPOSE, 18) => Ok(ExecutorOption::LzCompose {
+            index: u16::from_be_bytes([params[0], params[1]]),
+            gas: read_u128(&params[2..18]),
+            value: 0,
+        }),
+        (OPTION_TYPE_LZCOMPOSE, 34) => Ok(ExecutorOption::LzCompose {
+            index: u16::from_be_bytes([params[0], params[1]]),
+            gas: read_u128(&params[2..18]),
+            value: read_u128(&params[18..]),
+        }),
+        (OPTION_TYPE_ORDERED_EXECUTION, 0) => Ok(ExecutorOption::OrderedExecution),
+        _ => err!(OftError::InvalidOptions),
+    }
+}
+
+fn read_u128(bytes: &[u8]) -> u128 {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(bytes);
+    u128::from_be_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vectors from the EVM `OptionsBuilder`, starting from `newOptions()`
+
+    /// `addExecutorLzReceiveOption(200_000, 0)`
+    const LZ_RECEIVE: &str = "00030100110100000000000000000000000000030d40";
+
+    /// `addExecutorLzReceiveOption(200_000, 0.01 ether)`
+    const LZ_RECEIVE_WITH_VALUE: &str = concat!(
+        "00030100210100000000000000000000000000030d40",
+        "0000000000000000002386f26fc10000",
+    );
+
+    /// `addExecutorNativeDropOption(0.001 ether, RECEIVER)`
+    const NATIVE_DROP: &str = concat!(
+        "000301003102000000000000000000038d7ea4c68000",
+        "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8",
+    );
+
+    /// `addExecutorLzComposeOption(0, 50_000, 0)`
+    const LZ_COMPOSE: &str = "00030100130300000000000000000000000000000000c350";
+
+    /// `addExecutorLzReceiveOption(200_000, 0).addExecutorNativeDropOption(0.001 ether, RECEIVER)`
+    const LZ_RECEIVE_AND_NATIVE_DROP: &str = concat!(
+        "00030100110100000000000000000000000000030d40",
+        "01003102000000000000000000038d7ea4c68000",
+        "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8",
+    );
+
+    /// `addExecutorOrderedExecutionOption()`
+    const ORDERED_EXECUTION: &str = "000301000104";
+
+    const RECEIVER: &str = "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn assert_invalid(result: Result<impl std::fmt::Debug>) {
+        assert_eq!(result.unwrap_err(), OftError::InvalidOptions.into());
+    }
+
+    #[test]
+    fn parses_lz_receive_gas() {
+        assert_eq!(
+            executor_options(&hex(LZ_RECEIVE)).unwrap(),
+            vec![ExecutorOption::LzReceive { gas: 200_000, value: 0 }]
+        );
+    }
+
+    #[test]
+    fn parses_lz_receive_gas_and_value() {
+        assert_eq!(
+            executor_options(&hex(LZ_RECEIVE_WITH_VALUE)).unwrap(),
+            vec![ExecutorOption::LzReceive { gas: 200_000, value: 10_000_000_000_000_000 }]
+        );
+    }
+
+    #[test]
+    fn parses_native_drop() {
+        assert_eq!(
+            executor_options(&hex(NATIVE_DROP)).unwrap(),
+            vec![ExecutorOption::NativeDrop {
+                amount: 1_000_000_000_000_000,
+                receiver: hex(RECEIVER).try_into().unwrap(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_lz_compose() {
+        assert_eq!(
+            executor_options(&hex(LZ_COMPOSE)).unwrap(),
+            vec![ExecutorOption::LzCompose { index: 0, gas: 50_000, value: 0 }]
+        );
+    }
+
+    #[test]
+    fn parses_ordered_execution() {
+        assert_eq!(
+            executor_options(&hex(ORDERED_EXECUTION)).unwrap(),
+            vec![ExecutorOption::OrderedExecution]
+        );
+    }
+
+    #[test]
+    fn parses_worker_options() {
+        assert_eq!(
+            parse(&hex(LZ_RECEIVE)).unwrap(),
+            vec![WorkerOption {
+                worker_id: EXECUTOR_WORKER_ID,
+                option_type: OPTION_TYPE_LZRECEIVE,
+                params: hex("00000000000000000000000000030d40"),
+            }]
+        );
+        assert!(parse(&[]).unwrap().is_empty());
+        assert!(parse(&hex("0003")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_other_option_types() {
+        assert_invalid(parse(&hex("00010100110100000000000000000000000000030d40")));
+        assert_invalid(parse(&hex("00")));
+    }
+
+    #[test]
+    fn rejects_malformed_sizes() {
+        // Size 0 leaves no room for the option type
+        assert_invalid(parse(&hex("000301000001")));
+        // Size runs past the end of the options
+        assert_invalid(parse(&hex("00030100120100000000000000000000000000030d40")));
+        // Truncated option header
+        assert_invalid(parse(&hex("000301001101")[..5]));
+        assert_invalid(parse(&hex(&LZ_RECEIVE[..LZ_RECEIVE.len() - 2])));
+    }
+
+    #[test]
+    fn rejects_executor_params_of_wrong_length() {
+        for options in [
+            // lzReceive with 15 and 17 bytes of params
+            "000301001001000000000000000000000000030d40",
+            "0003010012010000000000000000000000000000030d40",
+            // Native drop without a receiver
+            "000301001102000000000000000000038d7ea4c68000",
+            // lzCompose without an index
+            "0003010011030000000000000000000000000000c350",
+        ] {
+            assert_invalid(executor_options(&hex(options)));
+        }
+    }
+
+    #[test]
+    fn combine_appends_caller_options_to_enforced() {
+        let combined = combine(&hex(LZ_RECEIVE), &hex(NATIVE_DROP)).unwrap();
+
+        assert_eq!(combined, hex(LZ_RECEIVE_AND_NATIVE_DROP));
+        assert_eq!(
+            executor_options(&combined).unwrap(),
+            vec![
+                ExecutorOption::LzReceive { gas: 200_000, value: 0 },
+                ExecutorOption::NativeDrop {
+                    amount: 1_000_000_000_000_000,
+                    receiver: hex(RECEIVER).try_into().unwrap(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn combine_uses_either_side_alone() {
+        assert_eq!(combine(&hex(LZ_RECEIVE), &[]).unwrap(), hex(LZ_RECEIVE));
+        assert_eq!(combine(&[], &hex(LZ_COMPOSE)).unwrap(), hex(LZ_COMPOSE));
+        assert!(combine(&[], &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn combine_rejects_caller_options_of_other_types() {
+        assert_invalid(combine(
+            &hex(LZ_RECEIVE),
+            &hex("00010100110100000000000000000000000000030d40"),
+        ));
+        assert_invalid(combine(&hex(LZ_RECEIVE), &hex("00")));
+    }
+}
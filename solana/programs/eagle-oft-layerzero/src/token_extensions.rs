@@ -0,0 +1,306 @@
+//! Token-2022 mint extensions: transfer fees and hooks, which affect
+//! bridging in lock/unlock mode, and the metadata created with the mint.
+//!
+//! Classic SPL Token mints have no extensions and pass through unchanged.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_spl::token_2022::spl_token_2022::onchain::invoke_transfer_checked;
+use anchor_spl::token_interface::TransferChecked;
+use anchor_spl::token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_hook, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::get_extra_account_metas_address;
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+use spl_type_length_value::state::TlvStateBorrowed;
+
+use crate::OftError;
+
+/// Fee withheld by the mint's transfer-fee extension when transferring
+/// `amount` in the current epoch
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(OftError::Overflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Amount to transfer so that `net_amount` arrives after the transfer fee
+pub fn gross_amount(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => {
+            let fee = config
+                .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+                .ok_or(OftError::Overflow)?;
+            net_amount.checked_add(fee).ok_or(OftError::Overflow.into())
+        }
+        Err(_) => Ok(net_amount),
+    }
+}
+
+/// Transfer-hook program invoked on every transfer of the mint, if any
+pub fn transfer_hook_program(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    Ok(transfer_hook::get_program_id(&mint))
+}
+
+/// `transfer_checked` that also runs the mint's transfer hook. The hook
+/// program, its extra-account-metas account and the accounts listed there
+/// are looked up in the context's remaining accounts, which are ignored
+/// for mints without a hook.
+pub fn transfer_checked<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Accounts the mint's transfer hook needs for a transfer of `amount` from
+/// `source` to `destination`: the hook program, its extra-account-metas
+/// account and the accounts listed there. Empty for mints without a hook.
+///
+/// `extra_account_metas` must be the hook's extra-account-metas account.
+/// Accounts derived from other accounts' data cannot be resolved here.
+pub fn transfer_hook_accounts(
+    mint: &AccountInfo,
+    extra_account_metas: Option<&AccountInfo>,
+    source: Pubkey,
+    destination: Pubkey,
+    authority: Pubkey,
+    amount: u64,
+) -> Result<Vec<AccountMeta>> {
+    let Some(hook_program) = transfer_hook_program(mint)? else {
+        return Ok(Vec::new());
+    };
+    let validation = get_extra_account_metas_address(mint.key, &hook_program);
+    let extra_account_metas = extra_account_metas
+        .filter(|account| account.key() == validation)
+        .ok_or(OftError::InvalidTransferHookAccounts)?;
+
+    let data = extra_account_metas.try_borrow_data()?;
+    let state = TlvStateBorrowed::unpack(&data)?;
+    let extra_metas = ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&state)?;
+    let instruction_data = TransferHookInstruction::Execute { amount }.pack();
+
+    // The hook's `Execute` accounts, which extra accounts may refer to by index
+    let mut accounts = vec![
+        AccountMeta::new_readonly(source, false),
+        AccountMeta::new_readonly(*mint.key, false),
+        AccountMeta::new_readonly(destination, false),
+        AccountMeta::new_readonly(authority, false),
+        AccountMeta::new_readonly(validation, false),
+    ];
+    for extra_meta in extra_metas.data() {
+        let meta = extra_meta.resolve(&instruction_data, &hook_program, |index| {
+            accounts.get(index).map(|meta| (&meta.pubkey, None))
+        })?;
+        accounts.push(meta);
+    }
+
+    let mut hook_accounts = vec![AccountMeta::new_readonly(hook_program, false)];
+    hook_accounts.extend(accounts.into_iter().skip(4));
+    Ok(hook_accounts)
+}
+
+/// Bytes the mint account grows by when `name`, `symbol` and `uri` are
+/// written to its metadata extension
+pub fn metadata_space(
+    mint: Pubkey,
+    update_authority: Pubkey,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Result<usize> {
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(update_authority),
+        mint,
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: uri.to_string(),
+        additional_metadata: Vec::new(),
+    };
+
+    Ok(metadata.tlv_size_of()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    };
+    use spl_tlv_account_resolution::account::ExtraAccountMeta;
+    use spl_tlv_account_resolution::seeds::Seed;
+
+    fn mint_data(hook_program: Option<Pubkey>) -> Vec<u8> {
+        let extensions: &[ExtensionType] = match hook_program {
+            Some(_) => &[ExtensionType::TransferHook],
+            None => &[],
+        };
+        let mut data =
+            vec![0u8; ExtensionType::try_calculate_account_len::<MintState>(extensions).unwrap()];
+        let mut mint = StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        if let Some(hook_program) = hook_program {
+            let transfer_hook = mint.init_extension::<TransferHook>(true).unwrap();
+            transfer_hook.program_id = Some(hook_program).try_into().unwrap();
+        }
+        mint.base.decimals = 9;
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        if hook_program.is_some() {
+            mint.init_account_type().unwrap();
+        }
+        data
+    }
+
+    fn extra_account_metas_data(metas: &[ExtraAccountMeta]) -> Vec<u8> {
+        let mut data = vec![0u8; ExtraAccountMetaList::size_of(metas.len()).unwrap()];
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, metas).unwrap();
+        data
+    }
+
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey, data: Vec<u8>) -> Self {
+            Self { key, owner: Pubkey::default(), lamports: 0, data }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                false,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    #[test]
+    fn resolves_transfer_hook_accounts() {
+        let hook_program = Pubkey::new_unique();
+        let fixed = Pubkey::new_unique();
+        let (source, destination, authority) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut mint = TestAccount::new(Pubkey::new_unique(), mint_data(Some(hook_program)));
+        let validation = get_extra_account_metas_address(&mint.key, &hook_program);
+        let mut extra_account_metas = TestAccount::new(
+            validation,
+            extra_account_metas_data(&[
+                ExtraAccountMeta::new_with_pubkey(&fixed, false, true).unwrap(),
+                // Per-destination PDA of the hook program
+                ExtraAccountMeta::new_with_seeds(
+                    &[
+                        Seed::Literal { bytes: b"counter".to_vec() },
+                        Seed::AccountKey { index: 2 },
+                    ],
+                    false,
+                    true,
+                )
+                .unwrap(),
+            ]),
+        );
+
+        let accounts = transfer_hook_accounts(
+            &mint.info(),
+            Some(&extra_account_metas.info()),
+            source,
+            destination,
+            authority,
+            1_000,
+        )
+        .unwrap();
+
+        let counter =
+            Pubkey::find_program_address(&[b"counter", destination.as_ref()], &hook_program).0;
+        assert_eq!(
+            accounts,
+            vec![
+                AccountMeta::new_readonly(hook_program, false),
+                AccountMeta::new_readonly(validation, false),
+                AccountMeta::new(fixed, false),
+                AccountMeta::new(counter, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn mints_without_hook_need_no_accounts() {
+        let mut mint = TestAccount::new(Pubkey::new_unique(), mint_data(None));
+
+        let accounts = transfer_hook_accounts(
+            &mint.info(),
+            None,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000,
+        )
+        .unwrap();
+
+        assert!(accounts.is_empty());
+        assert_eq!(transfer_hook_program(&mint.info()).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_missing_or_wrong_extra_account_metas() {
+        let hook_program = Pubkey::new_unique();
+        let mut mint = TestAccount::new(Pubkey::new_unique(), mint_data(Some(hook_program)));
+        let mut wrong = TestAccount::new(Pubkey::new_unique(), extra_account_metas_data(&[]));
+        let (source, destination, authority) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_eq!(transfer_hook_program(&mint.info()).unwrap(), Some(hook_program));
+        for extra_account_metas in [None, Some(&wrong.info())] {
+            assert_eq!(
+                transfer_hook_accounts(
+                    &mint.info(),
+                    extra_account_metas,
+                    source,
+                    destination,
+                    authority,
+                    1_000,
+                )
+                .unwrap_err(),
+                OftError::InvalidTransferHookAccounts.into()
+            );
+        }
+    }
+}
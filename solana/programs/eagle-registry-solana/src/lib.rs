@@ -18,6 +18,7 @@ pub mod eagle_registry_solana {
         let registry = &mut ctx.accounts.registry_config;
         
         registry.authority = ctx.accounts.authority.key();
+        registry.pending_authority = None;
         registry.solana_eid = solana_eid;
         registry.wsol_address = wsol_address;
         registry.lz_endpoint = lz_endpoint;
@@ -52,6 +53,108 @@ pub mod eagle_registry_solana {
         Ok(())
     }
 
+    /// Propose a new authority
+    /// The authority only changes once the proposed key accepts
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.registry_config;
+        registry.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposed {
+            authority: registry.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Proposed authority transfer to {}", new_authority);
+
+        Ok(())
+    }
+
+    /// Accept a pending authority transfer
+    /// Must be signed by the proposed authority
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry_config;
+        let old_authority = registry.authority;
+        registry.authority = ctx.accounts.pending_authority.key();
+        registry.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            old_authority,
+            new_authority: registry.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Authority transferred from {} to {}", old_authority, registry.authority);
+
+        Ok(())
+    }
+
+    /// Cancel a pending authority transfer
+    /// Only the authority can call this
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry_config;
+        let pending_authority = registry
+            .pending_authority
+            .take()
+            .ok_or(ErrorCode::NoPendingAuthority)?;
+
+        emit!(AuthorityTransferCancelled {
+            authority: registry.authority,
+            pending_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Cancelled authority transfer to {}", pending_authority);
+
+        Ok(())
+    }
+
+    /// Grow a registry config created before `pending_authority` to the
+    /// current layout
+    /// The new field reads as `None` from the zeroed tail
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let registry = ctx.accounts.registry_config.to_account_info();
+        let space = 8 + RegistryConfig::INIT_SPACE;
+        require!(registry.data_len() < space, ErrorCode::AlreadyMigrated);
+
+        {
+            let data = registry.try_borrow_data()?;
+            require!(
+                data.len() >= 40 && data[..8] == *RegistryConfig::DISCRIMINATOR,
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            require_keys_eq!(
+                Pubkey::new_from_array(data[8..40].try_into().unwrap()),
+                ctx.accounts.authority.key(),
+                anchor_lang::error::ErrorCode::ConstraintHasOne
+            );
+        }
+
+        let top_up = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(registry.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: registry.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        registry.resize(space)?;
+
+        msg!("Registry config migrated");
+
+        Ok(())
+    }
+
     /// Register a new EVM chain that can send messages to Solana
     /// This stores minimal metadata about cross-chain peers
     pub fn register_peer_chain(
@@ -200,6 +303,64 @@ pub struct UpdateConfig<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry_config.bump,
+        has_one = authority,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry_config.bump,
+        constraint = registry_config.pending_authority == Some(pending_authority.key())
+            @ ErrorCode::NotPendingAuthority,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+    
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry_config.bump,
+        has_one = authority,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Config in the layout before `pending_authority`, so not deserialized
+    /// CHECK: Discriminator and authority are checked by the instruction
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump,
+        owner = crate::ID,
+    )]
+    pub registry_config: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(chain_eid: u32)]
 pub struct RegisterPeerChain<'info> {
@@ -290,6 +451,10 @@ pub struct RegistryConfig {
     
     /// PDA bump
     pub bump: u8,
+    
+    /// Proposed authority awaiting acceptance
+    /// (appended after the original layout; see `migrate_config`)
+    pub pending_authority: Option<Pubkey>,
 }
 
 #[account]
@@ -331,6 +496,27 @@ pub struct QuerySent {
     pub query_type: u8,
 }
 
+#[event]
+pub struct AuthorityTransferProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
 // ============================================================================
 // Errors
 // ============================================================================
@@ -357,5 +543,14 @@ pub enum ErrorCode {
     
     #[msg("Chain name too long (max 32 characters)")]
     NameTooLong,
+    
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    
+    #[msg("Registry config already has the current layout")]
+    AlreadyMigrated,
 }
 
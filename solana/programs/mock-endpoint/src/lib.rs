@@ -0,0 +1,448 @@
+//! Mock LayerZero V2 endpoint for local tests of the EAGLE OFT.
+//!
+//! Implements the endpoint instructions the OFT invokes (`register_oapp`,
+//! `send`, `quote`, `clear`, `send_compose`, `set_delegate`) under the same
+//! names, params and account counts, so the OFT's hand-built CPIs reach it
+//! unchanged. Like the real endpoint, `clear` and `set_delegate` require the
+//! OApp's registry entry. Outbound nonces and GUIDs follow the real
+//! endpoint. The fee is a flat `native_fee` plus `fee_per_byte` for each
+//! message byte, paid to the endpoint settings account. Inbound payloads are
+//! not verified.
+//!
+//! Only for the local test validator; never deploy it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+declare_id!("Bmba7mZCgWmKnmPCo9cxx5hdJyt7LM6e34GV3CzHsqMS");
+
+pub const ENDPOINT_SEED: &[u8] = b"Endpoint";
+pub const NONCE_SEED: &[u8] = b"Nonce";
+pub const OAPP_SEED: &[u8] = b"OApp";
+
+#[program]
+pub mod mock_endpoint {
+    use super::*;
+
+    /// Create the endpoint settings for the local chain `eid`
+    pub fn init_endpoint(
+        ctx: Context<InitEndpoint>,
+        eid: u32,
+        native_fee: u64,
+        fee_per_byte: u64,
+    ) -> Result<()> {
+        let endpoint = &mut ctx.accounts.endpoint;
+        endpoint.eid = eid;
+        endpoint.native_fee = native_fee;
+        endpoint.fee_per_byte = fee_per_byte;
+        endpoint.bump = ctx.bumps.endpoint;
+
+        msg!("Mock endpoint initialized for EID {}", eid);
+
+        Ok(())
+    }
+
+    /// Create the registry entry of the signing OApp
+    pub fn register_oapp(ctx: Context<RegisterOApp>, params: RegisterOAppParams) -> Result<()> {
+        let registry = &mut ctx.accounts.oapp_registry;
+        registry.delegate = params.delegate;
+        registry.bump = ctx.bumps.oapp_registry;
+
+        msg!("Registered OApp {}", ctx.accounts.oapp.key());
+
+        Ok(())
+    }
+
+    /// Assign the next outbound nonce and GUID, charging the quoted fee
+    pub fn send(ctx: Context<Send>, params: SendParams) -> Result<MessagingReceipt> {
+        let fee = ctx.accounts.endpoint.fee(&params.message);
+        require!(params.lz_token_fee == 0, MockEndpointError::LzTokenUnavailable);
+        require!(
+            params.native_fee >= fee.native_fee,
+            MockEndpointError::InsufficientFee
+        );
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.endpoint.to_account_info(),
+                },
+            ),
+            fee.native_fee,
+        )?;
+
+        let nonce = &mut ctx.accounts.nonce;
+        nonce.outbound_nonce += 1;
+        nonce.bump = ctx.bumps.nonce;
+
+        let guid = guid(
+            nonce.outbound_nonce,
+            ctx.accounts.endpoint.eid,
+            ctx.accounts.sender.key(),
+            params.dst_eid,
+            params.receiver,
+        );
+
+        emit!(PacketSent {
+            guid,
+            nonce: nonce.outbound_nonce,
+            sender: ctx.accounts.sender.key(),
+            dst_eid: params.dst_eid,
+            receiver: params.receiver,
+            message: params.message,
+            options: params.options,
+        });
+
+        Ok(MessagingReceipt {
+            guid,
+            nonce: nonce.outbound_nonce,
+            fee,
+        })
+    }
+
+    /// Fee `send` charges for `params`
+    pub fn quote(ctx: Context<Quote>, params: QuoteParams) -> Result<MessagingFee> {
+        require!(!params.pay_in_lz_token, MockEndpointError::LzTokenUnavailable);
+
+        Ok(ctx.accounts.endpoint.fee(&params.message))
+    }
+
+    /// Accept any payload the receiving OApp clears
+    pub fn clear(ctx: Context<Clear>, params: ClearParams) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.signer.key(),
+            params.receiver,
+            MockEndpointError::Unauthorized
+        );
+
+        msg!("Cleared nonce {} from EID {}", params.nonce, params.src_eid);
+
+        Ok(())
+    }
+
+    /// Accept the compose message without queueing it
+    pub fn send_compose(ctx: Context<SendCompose>, params: SendComposeParams) -> Result<()> {
+        msg!(
+            "Compose from {} to {} (index {})",
+            ctx.accounts.from.key(),
+            params.to,
+            params.index
+        );
+
+        Ok(())
+    }
+
+    /// Record the OApp's delegate
+    pub fn set_delegate(ctx: Context<SetDelegate>, params: SetDelegateParams) -> Result<()> {
+        ctx.accounts.oapp_registry.delegate = params.delegate;
+
+        msg!("Delegate of {}: {}", ctx.accounts.oapp.key(), params.delegate);
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Account Structures
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitEndpoint<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EndpointSettings::INIT_SPACE,
+        seeds = [ENDPOINT_SEED],
+        bump
+    )]
+    pub endpoint: Account<'info, EndpointSettings>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterOApp<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The OApp, signing through CPI
+    pub oapp: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OAppRegistry::INIT_SPACE,
+        seeds = [OAPP_SEED, oapp.key().as_ref()],
+        bump
+    )]
+    pub oapp_registry: Account<'info, OAppRegistry>,
+
+    pub system_program: Program<'info, System>,
+    /// CHECK: Unused by the mock
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub program: UncheckedAccount<'info>,
+}
+
+/// The seven endpoint accounts of the real `send`, followed by stand-ins
+/// for the send library's accounts (payer and system program)
+#[derive(Accounts)]
+#[instruction(params: SendParams)]
+pub struct Send<'info> {
+    /// The OApp, signing through CPI
+    pub sender: Signer<'info>,
+
+    /// CHECK: Unused by the mock
+    pub send_library_program: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub send_library_config: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub default_send_library_config: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub send_library_info: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ENDPOINT_SEED],
+        bump = endpoint.bump
+    )]
+    pub endpoint: Account<'info, EndpointSettings>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Nonce::INIT_SPACE,
+        seeds = [
+            NONCE_SEED,
+            sender.key().as_ref(),
+            &params.dst_eid.to_be_bytes(),
+            &params.receiver,
+        ],
+        bump
+    )]
+    pub nonce: Account<'info, Nonce>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Same as `Send` without the sender and the library stand-ins
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    /// CHECK: Unused by the mock
+    pub send_library_program: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub send_library_config: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub default_send_library_config: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub send_library_info: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [ENDPOINT_SEED],
+        bump = endpoint.bump
+    )]
+    pub endpoint: Account<'info, EndpointSettings>,
+
+    /// CHECK: Unused by the mock
+    pub nonce: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Clear<'info> {
+    /// The receiving OApp, signing through CPI
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [OAPP_SEED, signer.key().as_ref()],
+        bump = oapp_registry.bump
+    )]
+    pub oapp_registry: Account<'info, OAppRegistry>,
+    /// CHECK: Unused by the mock
+    #[account(mut)]
+    pub nonce: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    #[account(mut)]
+    pub payload_hash: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    #[account(mut)]
+    pub endpoint: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SendCompose<'info> {
+    /// The OApp, signing through CPI
+    pub from: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Unused by the mock
+    #[account(mut)]
+    pub compose_message: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Unused by the mock
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    /// The OApp, signing through CPI
+    pub oapp: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [OAPP_SEED, oapp.key().as_ref()],
+        bump = oapp_registry.bump
+    )]
+    pub oapp_registry: Account<'info, OAppRegistry>,
+    /// CHECK: Unused by the mock
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub program: UncheckedAccount<'info>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct EndpointSettings {
+    pub eid: u32,
+    pub native_fee: u64,
+    pub fee_per_byte: u64,
+    pub bump: u8,
+}
+
+impl EndpointSettings {
+    pub fn fee(&self, message: &[u8]) -> MessagingFee {
+        MessagingFee {
+            native_fee: self.native_fee + self.fee_per_byte * message.len() as u64,
+            lz_token_fee: 0,
+        }
+    }
+}
+
+/// Registration of an OApp and its delegate
+#[account]
+#[derive(InitSpace)]
+pub struct OAppRegistry {
+    pub delegate: Pubkey,
+    pub bump: u8,
+}
+
+/// Outbound nonce of one sender / destination / receiver path
+#[account]
+#[derive(InitSpace)]
+pub struct Nonce {
+    pub outbound_nonce: u64,
+    pub bump: u8,
+}
+
+// ============================================================================
+// Params (layouts of the real endpoint)
+// ============================================================================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RegisterOAppParams {
+    pub delegate: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SendParams {
+    pub dst_eid: u32,
+    pub receiver: [u8; 32],
+    pub message: Vec<u8>,
+    pub options: Vec<u8>,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct QuoteParams {
+    pub sender: Pubkey,
+    pub dst_eid: u32,
+    pub receiver: [u8; 32],
+    pub message: Vec<u8>,
+    pub options: Vec<u8>,
+    pub pay_in_lz_token: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ClearParams {
+    pub receiver: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub guid: [u8; 32],
+    pub message: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SendComposeParams {
+    pub to: Pubkey,
+    pub guid: [u8; 32],
+    pub index: u16,
+    pub message: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SetDelegateParams {
+    pub delegate: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MessagingFee {
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MessagingReceipt {
+    pub guid: [u8; 32],
+    pub nonce: u64,
+    pub fee: MessagingFee,
+}
+
+#[event]
+pub struct PacketSent {
+    pub guid: [u8; 32],
+    pub nonce: u64,
+    pub sender: Pubkey,
+    pub dst_eid: u32,
+    pub receiver: [u8; 32],
+    pub message: Vec<u8>,
+    pub options: Vec<u8>,
+}
+
+/// `keccak256(nonce | src_eid | sender | dst_eid | receiver)`, integers big-endian
+pub fn guid(nonce: u64, src_eid: u32, sender: Pubkey, dst_eid: u32, receiver: [u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[
+        &nonce.to_be_bytes(),
+        &src_eid.to_be_bytes(),
+        sender.as_ref(),
+        &dst_eid.to_be_bytes(),
+        &receiver,
+    ])
+    .to_bytes()
+}
+
+#[error_code]
+pub enum MockEndpointError {
+    #[msg("Native fee below the quote")]
+    InsufficientFee,
+
+    #[msg("LZ token fees are not supported by the mock")]
+    LzTokenUnavailable,
+
+    #[msg("Signer is not the receiving OApp")]
+    Unauthorized,
+}